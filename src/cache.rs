@@ -0,0 +1,119 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+
+/// Identifies a count by weight, context (the program including context and projection rules,
+/// and solver arguments restricting answer sets) and the set of route literals.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Key {
+    weight: String,
+    context: u64,
+    route: Vec<String>,
+}
+impl Key {
+    pub fn new(weight: String, program: &str, args: &[String], route: &[String]) -> Self {
+        let mut hasher = DefaultHasher::new();
+        program.hash(&mut hasher);
+        args.hash(&mut hasher);
+
+        let mut route = route.to_vec();
+        route.sort();
+        route.dedup();
+
+        Self {
+            weight,
+            context: hasher.finish(),
+            route,
+        }
+    }
+}
+
+/// Number of counts kept before the oldest ones are evicted.
+pub const CAPACITY: usize = 1 << 16;
+
+/// Counts and weights computed so far in the session, at most [`CAPACITY`] of them; once full,
+/// the entry inserted first is evicted.
+#[derive(Debug, Default)]
+pub struct Cache {
+    counts: HashMap<Key, usize>,
+    weights: HashMap<Key, f64>,
+    order: VecDeque<Key>,
+    hits: usize,
+    misses: usize,
+}
+impl Cache {
+    pub fn get(&mut self, key: &Key) -> Option<usize> {
+        let c = self.counts.get(key).copied();
+        self.record(c.is_some());
+        c
+    }
+
+    pub fn insert(&mut self, key: Key, count: usize) {
+        if self.counts.insert(key.clone(), count).is_none() {
+            self.push(key);
+        }
+    }
+
+    /// Returns the weight, as opposed to the count, stored under **key**.
+    pub fn get_weight(&mut self, key: &Key) -> Option<f64> {
+        let w = self.weights.get(key).copied();
+        self.record(w.is_some());
+        w
+    }
+
+    pub fn insert_weight(&mut self, key: Key, weight: f64) {
+        if self.weights.insert(key.clone(), weight).is_none() {
+            self.push(key);
+        }
+    }
+
+    fn record(&mut self, hit: bool) {
+        match hit {
+            true => self.hits += 1,
+            _ => self.misses += 1,
+        }
+    }
+
+    fn push(&mut self, key: Key) {
+        self.order.push_back(key);
+        if self.order.len() > CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.counts.remove(&oldest);
+                self.weights.remove(&oldest);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn stats(&self) -> String {
+        format!(
+            "entries {} hits {} misses {}",
+            self.order.len(),
+            self.hits,
+            self.misses
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(i: usize) -> Key {
+        Key::new("fc".to_owned(), "a;b.", &[], &[format!("a{i}")])
+    }
+
+    #[test]
+    fn evicts_oldest_count_at_capacity() {
+        let mut cache = Cache::default();
+        (0..=CAPACITY).for_each(|i| cache.insert(key(i), i));
+
+        assert_eq!(cache.counts.len(), CAPACITY);
+        assert_eq!(cache.get(&key(0)), None);
+        assert_eq!(cache.get(&key(1)), Some(1));
+        assert_eq!(cache.get(&key(CAPACITY)), Some(CAPACITY));
+    }
+}
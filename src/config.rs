@@ -14,6 +14,7 @@ pub const ANSWER_SET_COUNT: &'static str = "#!";
 pub const ANSWER_SET_COUNTS: &'static str = "#!!";
 pub const ENUMERATE_SOLUTIONS: &'static str = "!";
 pub const ENUMERATE_PROJECTED_SOLUTIONS: &'static str = "!*";
+pub const SAMPLE_SOLUTIONS: &'static str = "!~";
 pub const SHOW_ROUTE: &'static str = "@";
 pub const CLEAR_ROUTE: &'static str = "--";
 pub const DEL_LAST: &'static str = "-";
@@ -21,6 +22,18 @@ pub const CHANGE_MODE: &'static str = "'";
 pub const DISPLAY_MODE: &'static str = ":mode";
 pub const PROPOSE_STEP: &'static str = "$";
 pub const TAKE_STEP: &'static str = "$$";
+pub const SAMPLE: &'static str = ":sample";
+pub const WEIGHTS: &'static str = ":weights";
+pub const WEIGH: &'static str = ":weigh";
+pub const OPTIMALITY: &'static str = ":opt";
+pub const COSTS: &'static str = ":costs";
+pub const PROJECT: &'static str = ":project";
+pub const AUTO: &'static str = ":auto";
+pub const BENCHMARK_MODES: &'static str = ":benchmark-modes";
+pub const TIE_BREAK: &'static str = ":ties";
+pub const TIMEOUT: &'static str = ":timeout";
+pub const CACHE: &'static str = ":cache";
+pub const CHECK_FACETS: &'static str = ":check_facets";
 pub const SHOW_PROGRAM: &'static str = ":src";
 pub const SHOW_ATOMS: &'static str = ":atoms";
 pub const FILTER_ATOMS: &'static str = ":filter_atoms";
@@ -28,6 +41,15 @@ pub const IS_ATOM: &'static str = ":isatom";
 pub const SOE: &'static str = ":soe";
 pub const CONTEXT: &'static str = ">";
 pub const SIGNIFICANCE: &'static str = "%";
+pub const SIGNIFICANCE_ANSWER_SETS: &'static str = "%#a";
+pub const SIGNIFICANCE_MATRIX: &'static str = "%%";
+pub const IMPLICATIONS: &'static str = ":implications";
+pub const GRAPH: &'static str = ":graph";
+pub const DIFF: &'static str = ":diff";
+pub const CLUSTER: &'static str = ":cluster";
+pub const ACTIVATE_SOLUTION: &'static str = "+@";
+pub const EXPORT_SOLUTION: &'static str = ":export";
+pub const DISTINGUISH: &'static str = ":distinguish";
 pub const SIGNIFICANCE_PROJECTING: &'static str = "!%";
 pub const IS_FACET: &'static str = ":?";
 pub const IS_FACET_R: &'static str = ":?r";
@@ -39,7 +61,8 @@ pub const FILTER_KEYWORD: &'static str = "%filter ";
 pub(crate) fn manual() {
     println!("display facet-inducing atoms                                                                  ->  {SHOW_FACETS}");
     println!("display route                                                                                 ->  {SHOW_ROUTE}");
-    println!("enumerate n=[int] answer sets                                                                 ->  {ENUMERATE_SOLUTIONS} n");
+    println!("enumerate n=[int] answer sets, exported as clingo JSON or CSV over filtered atoms if given    ->  {ENUMERATE_SOLUTIONS} n --json filename --csv filename");
+    println!("draw n=[int] answer sets uniformly at random, reproducible by seed=[int]                      ->  {SAMPLE_SOLUTIONS} n seed");
     println!("activate facets=[whitespace seperated literals, e.g., a ~b]                                   ->  {ACTIVATE_FACETS} facets"); 
     println!("activate facets=[whitespace seperated literals, e.g., a ~b] and display consequences          ->  {ACTIVATE_FACETS_LT} facets");
     println!("deactivate previous facet                                                                     ->  {DEL_LAST}");
@@ -47,24 +70,47 @@ pub(crate) fn manual() {
     //println!("check whether regex-matched atoms are facets                                                  ->  {IS_FACET} regex"); 
     //println!("check whether regex-matched atoms are facets with one consistency check                       ->  {IS_FACET_R} regex"); 
     println!("declare cnf=[e.g., a|~b&c|d] context/query                                                    ->  {CONTEXT} cnf");
-    println!("select navigation mode=[{{{{min,max}}#{{f,a,s}}, go}}]                                              ->  {CHANGE_MODE} mode");
+    println!("select navigation mode=[{{{{min,max}}#{{f,a,s,w}}, opt, go, random seed}}]                          ->  {CHANGE_MODE} mode");
     println!("next step in mode                                                                             ->  {PROPOSE_STEP}");
+    println!("top k=[number] candidate steps in mode ranked, optionally among facets matching regex         ->  {PROPOSE_STEP} k regex");
     println!("perform next step in mode                                                                     ->  {TAKE_STEP}");
+    println!("perform i-th=[number] ranked candidate step in mode, optionally among facets matching regex   ->  {TAKE_STEP} i regex");
+    println!("sample n=[number] random navigations to an answer set, reproducible by seed=[number]          ->  {SAMPLE} n seed");
+    println!("list weights, or activate=[use name] or load=[load name filename] weighted facet counting     ->  {WEIGHTS} action");
+    println!("weights under each facet filtered from current facets by regex                                ->  {WEIGH} regex");
+    println!("restrict answer sets, restriction=[optimal, bound costs e.g. 3 or 3,1, off]                   ->  {OPTIMALITY} restriction");
+    println!("best reachable costs under each facet filtered from current facets by regex                   ->  {COSTS} regex");
+    println!("steps count answer sets projected onto atoms matching target=[regex or off]                   ->  {PROJECT} target");
+    println!("perform steps in mode until any of criteria=[fc k, as k, goal literal, time secs] holds       ->  {AUTO} criteria");
+    println!("navigate to an answer set in each mode, report steps, routes counted, time and answer set     ->  {BENCHMARK_MODES}");
+    println!("break ties of steps by policy=[solver, lex, priority preds.., weights filename, random seed]  ->  {TIE_BREAK} policy");
+    println!("bound steps by time budget=[seconds, 0 removes budget], Ctrl-C stops after the running count  ->  {TIMEOUT} budget");
     println!("compute facets among atoms that match targets=[regex]                                         ->  {COMPUTE_FACETS} targets");
     println!("facet count of facet                                                                          ->  {FACET_COUNT} facet");
     println!("facet counts under each facet filtered from current facets by regex                           ->  {FACET_COUNTS} regex");
     //println!("facet count w.r.t. to provided weights in given filename                                      ->  {WEIGHTED_FACET_COUNT} filename facet");
     //println!("facet counts under each facet w.r.t. to provided regex and weights in given filename          ->  {WEIGHTED_FACET_COUNTS} filename regex");
     println!("facet counts under each facet in targets_1=[regex] using projection on targets_2=[regex]      ->  {FACET_COUNTS_PROJECTING} targets_2 targets_1");
-    println!("significance of facets=[regex] for some literal=[a or ~a]                                     ->  {SIGNIFICANCE} literal facets");
-    println!("significance of facets=[regex] for some literal=[a or ~a] using projection on targets=[regex] ->  {SIGNIFICANCE_PROJECTING} literal targets facets ");
+    println!("significance of facets=[regex] for literal=[a or ~a], options=[sort inc|exc,top k,inc|exc t]  ->  {SIGNIFICANCE} literal facets options");
+    println!("significance of facets=[regex] for literal=[a or ~a] by answer set counting, options as in %  ->  {SIGNIFICANCE_ANSWER_SETS} literal facets options");
+    println!("significance of facets=[regex] for each of literals=[regex] as CSV, to stdout or filename     ->  {SIGNIFICANCE_MATRIX} literals facets filename");
+    println!("significance of facets=[regex] for some literal=[a or ~a] using projection on targets=[regex] ->  {SIGNIFICANCE_PROJECTING} literal targets facets options");
+    println!("entailments and exclusions among facets=[regex], written as DOT graph to filename if given    ->  {IMPLICATIONS} facets filename");
+    println!("write facets=[regex] with counts and their implications in format=[dot, graphml] to filename  ->  {GRAPH} format filename facets");
     println!("answer set count                                                                              ->  {ANSWER_SET_COUNT}");
     println!("answer set counts under each facet                                                            ->  {ANSWER_SET_COUNTS}");
-    println!("enumerate representative answer sets regarding targets=[regex] filtered from current facets   ->  {SOE} targets");
+    println!("representatives covering targets=[regex] among facets, options=[max k, dist d, greedy]        ->  {SOE} targets options");
+    println!("atoms only in, only in and common to answer sets=[i or ri, i-th solution or representative]   ->  {DIFF} i j");
+    println!("group solutions, or representatives if r, by least Jaccard similarity=[float, default 0.5]    ->  {CLUSTER} r similarity");
+    println!("activate literals over current facets of answer set=[i or ri, as in :diff]                    ->  {ACTIVATE_SOLUTION}i");
+    println!("write answer set=[i or ri, as in :diff] as facts to filename                                  ->  {EXPORT_SOLUTION} i filename");
+    println!("literals over current facets that exclude other solutions, or representatives if ri           ->  {DISTINGUISH} i");
     println!("display program                                                                               ->  {SHOW_PROGRAM}");
     println!("display atoms                                                                                 ->  {SHOW_ATOMS}");
     println!("display regex-matched atoms                                                                   ->  {FILTER_ATOMS} regex");
     println!("atom check                                                                                    ->  {IS_ATOM}");
+    println!("display statistics of cached counts or clear them, action=[stats or clear]                    ->  {CACHE} action");
+    println!("check incremental facets against facets computed from scratch, switch=[on or off]             ->  {CHECK_FACETS} switch");
     println!("display navigation mode                                                                       ->  {DISPLAY_MODE}");
     println!("quit                                                                                          ->  {QUIT}");
     //println!("see documentation for more details");
@@ -0,0 +1,7 @@
+//! Programs and helpers shared by tests.
+
+pub const TINY: &str = "a;b. c;d :- b. e.";
+
+pub fn strings(xs: &[&str]) -> Vec<String> {
+    xs.iter().map(|x| x.to_string()).collect()
+}
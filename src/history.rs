@@ -0,0 +1,215 @@
+use savan::lex;
+use savan::nav::{facets::Facets, Navigator};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+
+/// Returns facets under **route**, computed from scratch.
+pub fn facets_from_scratch(nav: &mut Navigator, route: &[String]) -> Option<Vec<String>> {
+    nav.facet_inducing_atoms(route.iter())
+        .map(|fs| fs.iter().map(|f| lex::repr(*f)).collect())
+}
+
+fn canonical(nav: &Navigator, route: &[String]) -> (u64, Vec<String>) {
+    let mut hasher = DefaultHasher::new();
+    nav.program().hash(&mut hasher);
+
+    let mut route = route.to_vec();
+    route.sort();
+    route.dedup();
+
+    (hasher.finish(), route)
+}
+
+/// Number of routes whose facets are kept before the oldest ones are evicted.
+pub const CAPACITY: usize = 1 << 10;
+
+/// Facets computed under earlier routes, per program, for at most [`CAPACITY`] routes.
+///
+/// Activating literals can only shrink the set of facets, hence facets under a route are
+/// searched among the facets under the largest known subroute.
+#[derive(Debug, Default)]
+pub struct History {
+    facets: HashMap<(u64, Vec<String>), Vec<String>>,
+    order: VecDeque<(u64, Vec<String>)>,
+    /// Compares every result to facets computed from scratch.
+    pub check: bool,
+}
+impl History {
+    /// Returns facets under **route**.
+    pub fn facets(&mut self, nav: &mut Navigator, route: &[String]) -> Option<Vec<String>> {
+        let key = canonical(nav, route);
+
+        let fs = match self.facets.get(&key) {
+            Some(fs) => fs.clone(),
+            _ => match self.base(&key) {
+                Some(prev) => {
+                    let bc = nav
+                        .brave_consequences(route.iter())?
+                        .iter()
+                        .map(|a| lex::repr(*a))
+                        .collect::<HashSet<_>>();
+                    let cc = nav
+                        .cautious_consequences(route.iter())?
+                        .iter()
+                        .map(|a| lex::repr(*a))
+                        .collect::<HashSet<_>>();
+                    prev.iter()
+                        .filter(|f| bc.contains(*f) && !cc.contains(*f))
+                        .cloned()
+                        .collect()
+                }
+                _ => facets_from_scratch(nav, route)?,
+            },
+        };
+
+        let fs = match self.check {
+            true => self.verify(nav, route, fs)?,
+            _ => fs,
+        };
+        self.insert(key, fs.clone());
+
+        Some(fs)
+    }
+
+    /// Forgets all facets, e.g., after the context changed.
+    pub fn clear(&mut self) {
+        self.facets.clear();
+        self.order.clear();
+    }
+
+    /// Remembers facets **fs** under **key**, evicting the oldest route once full.
+    fn insert(&mut self, key: (u64, Vec<String>), fs: Vec<String>) {
+        if self.facets.insert(key.clone(), fs).is_some() {
+            return;
+        }
+        self.order.push_back(key);
+        if self.order.len() > CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.facets.remove(&oldest);
+            }
+        }
+    }
+
+    /// Returns facets under the largest known subroute of **route** of the same program.
+    fn base(&self, (program, route): &(u64, Vec<String>)) -> Option<&Vec<String>> {
+        self.facets
+            .iter()
+            .filter(|((p, r), _)| p == program && r.iter().all(|l| route.binary_search(l).is_ok()))
+            .max_by_key(|((_, r), _)| r.len())
+            .map(|(_, fs)| fs)
+    }
+
+    fn verify(
+        &self,
+        nav: &mut Navigator,
+        route: &[String],
+        fs: Vec<String>,
+    ) -> Option<Vec<String>> {
+        let expected = facets_from_scratch(nav, route)?;
+        let (xs, ys) = (
+            fs.iter().collect::<HashSet<_>>(),
+            expected.iter().collect::<HashSet<_>>(),
+        );
+        match xs == ys {
+            true => Some(fs),
+            _ => {
+                println!("error: inconsistent facets under route {:?}", route);
+                println!("missing {:?}", ys.difference(&xs).collect::<Vec<_>>());
+                println!("spurious {:?}", xs.difference(&ys).collect::<Vec<_>>());
+                Some(expected)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::TINY;
+    use savan::nav::errors::*;
+
+    const Q8: &str = "
+    {q(I ,1..8)} == 1 :- I = 1..8.
+    {q(1..8, J)} == 1 :- J = 1..8.
+    :- {q(D-J, J)} >= 2, D = 2..2*8.
+    :- {q(D+J, J)} >= 2, D = 1-8..8-1.";
+
+    fn assert_same(xs: &[String], ys: &[String]) {
+        assert_eq!(
+            xs.iter().collect::<HashSet<_>>(),
+            ys.iter().collect::<HashSet<_>>()
+        );
+    }
+
+    #[test]
+    fn tiny_incremental() -> Result<()> {
+        let mut nav = Navigator::new(TINY, vec!["0".to_string()])?;
+        let mut history = History::default();
+        let mut route = vec![];
+        history
+            .facets(&mut nav, &route)
+            .ok_or(NavigatorError::None)?;
+
+        for lit in ["b", "~c"] {
+            route.push(lit.to_string());
+            let fs = history
+                .facets(&mut nav, &route)
+                .ok_or(NavigatorError::None)?;
+            let expected = facets_from_scratch(&mut nav, &route).ok_or(NavigatorError::None)?;
+            assert_same(&fs, &expected);
+        }
+        assert!(history
+            .facets(&mut nav, &route)
+            .is_some_and(|fs| fs.is_empty()));
+
+        route.pop();
+        let fs = history
+            .facets(&mut nav, &route)
+            .ok_or(NavigatorError::None)?;
+        assert_same(&fs, &["c".to_string(), "d".to_string()]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn q8_incremental() -> Result<()> {
+        let mut nav = Navigator::new(Q8, vec!["0".to_string()])?;
+        let mut history = History::default();
+        let mut route = vec![];
+        history
+            .facets(&mut nav, &route)
+            .ok_or(NavigatorError::None)?;
+
+        for lit in ["~q(1,1)", "q(6,6)", "~q(3,1)", "q(2,8)"] {
+            route.push(lit.to_string());
+            let fs = history
+                .facets(&mut nav, &route)
+                .ok_or(NavigatorError::None)?;
+            let expected = facets_from_scratch(&mut nav, &route).ok_or(NavigatorError::None)?;
+            assert_same(&fs, &expected);
+        }
+
+        // order of activation does not matter
+        route.reverse();
+        let fs = history
+            .facets(&mut nav, &route)
+            .ok_or(NavigatorError::None)?;
+        let expected = facets_from_scratch(&mut nav, &route).ok_or(NavigatorError::None)?;
+        assert_same(&fs, &expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn evicts_oldest_route_at_capacity() {
+        let mut history = History::default();
+        (0..=CAPACITY).for_each(|i| history.insert((0, vec![format!("a{i}")]), vec![]));
+
+        assert_eq!(history.facets.len(), CAPACITY);
+        assert!(!history.facets.contains_key(&(0, vec!["a0".to_string()])));
+        assert!(history
+            .facets
+            .contains_key(&(0, vec![format!("a{CAPACITY}")])));
+    }
+}
@@ -0,0 +1,232 @@
+use crate::modes::Budget;
+use crate::pool::Counter;
+use savan::lex;
+use savan::nav::{facets::Facets, weights::Weight, Navigator};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Relation from one facet to another under some route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Relation {
+    /// Every answer set with the former facet contains the latter.
+    Entails,
+    /// No answer set contains both facets.
+    Excludes,
+}
+impl fmt::Display for Relation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Entails => write!(f, "entails"),
+            Self::Excludes => write!(f, "excludes"),
+        }
+    }
+}
+
+/// Implication graph among facets, where exclusions are listed once per pair.
+#[derive(Debug, Clone, Default)]
+pub struct Implications {
+    pub facets: Vec<String>,
+    pub edges: Vec<(String, Relation, String)>,
+    /// Facet and answer set counts under each facet, in order of facets, if annotated.
+    pub counts: Option<Vec<(Option<usize>, Option<usize>)>>,
+}
+impl Implications {
+    /// Relations among **facets** under **route**, using consequences under **route** extended
+    /// by each facet.
+    pub fn mine(
+        nav: &mut Navigator,
+        route: &[String],
+        facets: &[String],
+        budget: &Budget,
+    ) -> Option<Self> {
+        let mut edges = vec![];
+        for (i, a) in facets.iter().enumerate() {
+            if budget.exhausted() {
+                break;
+            }
+            let ctx = route.iter().chain([a]).collect::<Vec<_>>();
+            let bc = nav
+                .brave_consequences(ctx.iter())?
+                .iter()
+                .map(|x| lex::repr(*x))
+                .collect::<HashSet<_>>();
+            let cc = nav
+                .cautious_consequences(ctx.iter())?
+                .iter()
+                .map(|x| lex::repr(*x))
+                .collect::<HashSet<_>>();
+
+            for (j, b) in facets.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                if cc.contains(b) {
+                    edges.push((a.clone(), Relation::Entails, b.clone()));
+                } else if i < j && !bc.contains(b) {
+                    edges.push((a.clone(), Relation::Excludes, b.clone()));
+                }
+            }
+            budget.tick();
+        }
+
+        Some(Self {
+            facets: facets.to_vec(),
+            edges,
+            counts: None,
+        })
+    }
+
+    /// Annotates facets with facet and answer set counts under **route** extended by each facet.
+    pub fn annotate(&mut self, counter: &mut Counter, route: &[String]) {
+        let routes = self
+            .facets
+            .iter()
+            .map(|f| route.iter().chain([f]).cloned().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let fcs = counter.count_all(&Weight::FacetCounting, &routes);
+        let ass = counter.count_all(&Weight::AnswerSetCounting, &routes);
+        self.counts = Some(fcs.into_iter().zip(ass).collect());
+    }
+
+    fn count(&self, i: usize) -> Option<(Option<usize>, Option<usize>)> {
+        self.counts.as_ref().and_then(|cs| cs.get(i)).copied()
+    }
+
+    /// Graph in DOT, with entailments as arrows, exclusions as dashed lines and counts in labels.
+    pub fn to_dot(&self) -> String {
+        let mut dot = "digraph implications {\n".to_owned();
+        for (i, f) in self.facets.iter().enumerate() {
+            match self.count(i) {
+                Some((fc, ac)) => {
+                    let label = format!("{f}\\nfc {} as {}", shown(fc), shown(ac));
+                    dot.push_str(&format!("  {} [label={}];\n", quoted(f), quoted(&label)));
+                }
+                _ => dot.push_str(&format!("  {};\n", quoted(f))),
+            }
+        }
+        for (a, relation, b) in &self.edges {
+            let style = match relation {
+                Relation::Entails => "",
+                Relation::Excludes => " [style=dashed, dir=none]",
+            };
+            dot.push_str(&format!("  {} -> {}{style};\n", quoted(a), quoted(b)));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Graph in GraphML, with relations and counts as data.
+    pub fn to_graphml(&self) -> String {
+        let mut xml = [
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#,
+            r#"  <key id="fc" for="node" attr.name="facet count" attr.type="long"/>"#,
+            r#"  <key id="as" for="node" attr.name="answer set count" attr.type="long"/>"#,
+            r#"  <key id="relation" for="edge" attr.name="relation" attr.type="string"/>"#,
+            r#"  <graph id="implications" edgedefault="directed">"#,
+        ]
+        .join("\n");
+        xml.push('\n');
+        for (i, f) in self.facets.iter().enumerate() {
+            let data = match self.count(i) {
+                Some((fc, ac)) => [("fc", fc), ("as", ac)]
+                    .into_iter()
+                    .filter_map(|(k, c)| c.map(|c| format!(r#"<data key="{k}">{c}</data>"#)))
+                    .collect::<String>(),
+                _ => String::new(),
+            };
+            xml.push_str(&format!(r#"    <node id="{}">{data}</node>"#, escaped(f)));
+            xml.push('\n');
+        }
+        for (a, relation, b) in &self.edges {
+            let directed = *relation == Relation::Entails;
+            xml.push_str(&format!(
+                r#"    <edge source="{}" target="{}" directed="{directed}"><data key="relation">{relation}</data></edge>"#,
+                escaped(a),
+                escaped(b)
+            ));
+            xml.push('\n');
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+}
+
+fn shown(count: Option<usize>) -> String {
+    count.map_or("_".to_owned(), |c| c.to_string())
+}
+
+/// Quotes **id** for DOT.
+fn quoted(id: &str) -> String {
+    format!("\"{}\"", id.replace('"', "\\\""))
+}
+
+/// Escapes **text** for XML.
+fn escaped(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::TINY;
+    use savan::nav::errors::{NavigatorError, Result};
+
+    #[test]
+    fn tiny_implications() -> Result<()> {
+        let mut nav = Navigator::new(TINY, vec!["0".to_string()])?;
+        let facets = ["a", "b", "c", "d"].map(|f| f.to_owned());
+
+        let implications = Implications::mine(&mut nav, &[], &facets, &Budget::default())
+            .ok_or(NavigatorError::None)?;
+        let mut edges = implications
+            .edges
+            .iter()
+            .map(|(a, r, b)| format!("{a} {r} {b}"))
+            .collect::<Vec<_>>();
+        edges.sort();
+        assert_eq!(
+            edges,
+            [
+                "a excludes b",
+                "a excludes c",
+                "a excludes d",
+                "c entails b",
+                "c excludes d",
+                "d entails b"
+            ]
+        );
+        assert!(implications.to_dot().contains("  \"c\" -> \"b\";\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn tiny_annotated() -> Result<()> {
+        let mut nav = Navigator::new(TINY, vec!["0".to_string()])?;
+        let facets = ["a", "b"].map(|f| f.to_owned());
+
+        let mut implications = Implications::mine(&mut nav, &[], &facets, &Budget::default())
+            .ok_or(NavigatorError::None)?;
+        implications.annotate(&mut Counter::new(&mut nav, None), &[]);
+        assert_eq!(
+            implications.counts,
+            Some(vec![(Some(0), Some(1)), (Some(4), Some(2))])
+        );
+
+        let dot = implications.to_dot();
+        assert!(dot.contains(r#"  "b" [label="b\nfc 4 as 2"];"#));
+        let graphml = implications.to_graphml();
+        assert!(graphml
+            .contains(r#"<node id="b"><data key="fc">4</data><data key="as">2</data></node>"#));
+        assert!(graphml.contains(
+            r#"<edge source="a" target="b" directed="false"><data key="relation">excludes</data></edge>"#
+        ));
+
+        Ok(())
+    }
+}
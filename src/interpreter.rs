@@ -1,7 +1,16 @@
 use crate::config::*;
-use crate::is_facet;
-use crate::modes::{perform_next_step, propose_next_step, Mode};
-use crate::significance::Significance;
+use crate::history::History;
+use crate::implications::Implications;
+use crate::is_facet::{self, Diversity, Representatives};
+use crate::modes::{
+    perform_next_step, perform_nth_step, propose_next_step, rank_weighted, Budget, Mode, Step,
+};
+use crate::optimize::{optimum, Optimality};
+use crate::sample::{sample, SplitMix};
+use crate::session::Session;
+use crate::significance::{matrix_to_csv, Selection, Significance, SignificanceRow};
+use crate::solutions;
+use crate::ties::TieBreak;
 use crate::wfc::parse_weighted_facets_from_file;
 use crate::wfc::weighted_facet_count;
 use indicatif::{ProgressBar, ProgressState, ProgressStyle};
@@ -11,7 +20,7 @@ use savan::nav::{
     errors::{NavigatorError, Result},
     facets::Facets,
     soe::Collect,
-    weights::{count, count_projecting, Weight},
+    weights::Weight,
     Navigator,
 };
 use std::fmt::Write;
@@ -19,10 +28,66 @@ use std::thread;
 use std::time::Duration;
 use std::time::Instant;
 
+/// Prints significance **rows** for literal **y** under **route**, or why there are none.
+fn print_significance(
+    nav: &mut Navigator,
+    route: &[String],
+    y: &str,
+    rows: &[SignificanceRow],
+) -> Result<()> {
+    if rows.is_empty() {
+        match nav.enumerate_solutions_quietly(Some(2), route.iter().chain([&y.to_owned()]))? {
+            0 => {
+                println!("no facets, no answer set");
+                return Ok(());
+            }
+            1 => {
+                println!("no facets, unique answer set");
+                return Ok(());
+            }
+            _ => (),
+        }
+    }
+    println!(" inc   exc");
+    for row in rows {
+        println!("{:.3} {:.3} {}", row.inclusive, row.exclusive, row.facet);
+    }
+    Ok(())
+}
+
+/// Rules that project answer sets onto atoms among **atoms** that match **re**.
+fn projection(nav: &Navigator, atoms: &[String], re: Option<Regex>) -> String {
+    let xs = if let Some(re) = re {
+        atoms
+            .iter()
+            .filter(|a| re.is_match(a))
+            .cloned()
+            .collect::<Vec<_>>()
+    } else {
+        atoms.to_vec()
+    };
+
+    let mut or = ":-".to_owned();
+    xs.iter().for_each(|a| {
+        or = format!("{or} not {a},");
+    });
+    or = format!("{}.", &or[..or.len() - 1]);
+
+    let shows = nav
+        .symbols()
+        .filter(|(s, _)| xs.iter().any(|a| a.starts_with(s)))
+        .map(|(s, n)| format!("#show {s}/{n}."))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!("{shows}\n{or}")
+}
+
 pub trait Evaluate<T>
 where
     T: Clone + PartialEq + Eq,
 {
+    #[allow(clippy::too_many_arguments)]
     fn command(
         &mut self,
         expr: String,
@@ -31,6 +96,7 @@ where
         facets: &mut Vec<String>,
         route: &mut Vec<String>,
         ctx: &mut Vec<String>,
+        session: &mut Session,
     ) -> Result<()>;
 }
 impl Evaluate<Option<usize>> for Mode<Option<usize>> {
@@ -42,21 +108,48 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
         facets: &mut Vec<String>,
         route: &mut Vec<String>,
         ctx: &mut Vec<String>,
+        session: &mut Session,
     ) -> Result<()> {
         let e = expr.clone();
         let mut split_expr = e.as_str().split_whitespace();
 
+        // NOTE: steps count projected solutions while projection rules are part of the program
+        if let (Some(PROPOSE_STEP | TAKE_STEP), Some(re)) = (
+            e.split_whitespace().next(),
+            session.projection.as_ref().and_then(|s| Regex::new(s).ok()),
+        ) {
+            let rule = projection(nav, atoms, Some(re));
+            if !nav.program().contains(&rule) {
+                nav.add_rule(&rule)?;
+                let result = self.command(expr, nav, atoms, facets, route, ctx, session);
+                nav.remove_rule(&rule)?;
+                *facets = session
+                    .history
+                    .facets(nav, route)
+                    .ok_or(NavigatorError::None)?;
+                return result;
+            }
+        }
+
+        // NOTE: counts of the mode refer to the route and context they were computed under
+        match e.split_whitespace().next() {
+            Some(
+                ACTIVATE_FACETS | ACTIVATE_FACETS_LT | ACTIVATE_FACETS_LAZY | DEL_LAST
+                | CLEAR_ROUTE | CONTEXT | OPTIMALITY,
+            ) => self.update(None),
+            Some(cmd) if cmd.starts_with(ACTIVATE_SOLUTION) => self.update(None),
+            _ => (),
+        }
+
         match split_expr.next() {
             Some(ACTIVATE_FACETS) => {
                 split_expr.for_each(|f| {
                     route.push(f.to_owned());
                 });
-                *facets = nav
-                    .facet_inducing_atoms(route.iter())
-                    .ok_or(NavigatorError::None)?
-                    .iter()
-                    .map(|f| lex::repr(*f))
-                    .collect();
+                *facets = session
+                    .history
+                    .facets(nav, route)
+                    .ok_or(NavigatorError::None)?;
             }
             Some(ACTIVATE_FACETS_LT) => {
                 split_expr.for_each(|f| {
@@ -73,19 +166,16 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
             }
             Some(COMPUTE_FACETS) => {
                 let start = Instant::now();
+                let fs = session
+                    .history
+                    .facets(nav, route)
+                    .ok_or(NavigatorError::None)?;
                 *facets = if let Some(re) = split_expr.next().and_then(|s| Regex::new(r#s).ok()) {
-                    nav.facet_inducing_atoms(route.iter())
-                        .ok_or(NavigatorError::None)?
-                        .iter()
-                        .map(|f| lex::repr(*f))
-                        .filter(|a| re.is_match(&a))
+                    fs.into_iter()
+                        .filter(|a| re.is_match(a))
                         .collect::<Vec<_>>()
                 } else {
-                    nav.facet_inducing_atoms(route.iter())
-                        .ok_or(NavigatorError::None)?
-                        .iter()
-                        .map(|f| lex::repr(*f))
-                        .collect()
+                    fs
                 };
                 println!("time elapsed: {:?}", start.elapsed())
             }
@@ -185,30 +275,11 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
                 println!("ent time elapsed: {:?}", start.elapsed())
             }
             Some(COMPUTE_FACETS_SU) => {
-                let xs = if let Some(re) = split_expr.next().and_then(|s| Regex::new(r#s).ok()) {
-                    atoms
-                        .iter()
-                        .filter(|a| re.is_match(a))
-                        .cloned()
-                        .collect::<Vec<_>>()
-                } else {
-                    atoms.iter().cloned().collect::<Vec<_>>()
-                };
-
-                let mut or = ":-".to_owned();
-                xs.iter().for_each(|a| {
-                    or = format!("{or} not {a},");
-                });
-                or = format!("{}.", &or[..or.len() - 1]);
-
-                let shows = nav
-                    .symbols()
-                    .filter(|(s, _)| xs.iter().any(|a| a.starts_with(s)))
-                    .map(|(s, n)| format!("#show {s}/{n}."))
-                    .collect::<Vec<_>>()
-                    .join("\n");
-
-                let s = format!("{shows}\n{or}");
+                let s = projection(
+                    nav,
+                    atoms,
+                    split_expr.next().and_then(|s| Regex::new(r#s).ok()),
+                );
 
                 nav.add_rule(s.clone())?;
 
@@ -222,6 +293,7 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
                 nav.remove_rule(s)?;
             }
             Some("!?soe") => {
+                let start = Instant::now();
                 let xs = if let Some(re) = split_expr.next().and_then(|s| Regex::new(r#s).ok()) {
                     atoms
                         .iter()
@@ -229,39 +301,56 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
                         .cloned()
                         .collect::<Vec<_>>()
                 } else {
-                    atoms.iter().cloned().collect::<Vec<_>>()
+                    atoms.to_vec()
                 };
                 let shows = nav
                     .symbols()
-                    .filter(|(s, _)| xs.iter().any(|a| a.starts_with(s)))
-                    .map(|(s, n)| format!("#show {s}/{n}."))
-                    .collect::<Vec<_>>()
-                    .join("\n");
-                nav.add_rule(shows.clone()).unwrap();
-                let cc = nav.cautious_consequences_projecting(route.iter());
-                nav.remove_rule(shows).unwrap();
-
-                let ys = cc
-                    .map(|cc| {
-                        let cc_ = cc.iter().map(|s| s.to_string()).collect::<Vec<_>>();
-                        xs.iter()
-                            .filter(move |x| !cc_.contains(x))
-                            .cloned()
-                            .collect::<Vec<_>>()
+                    .filter(|(s, n)| {
+                        xs.iter().any(|a| match n {
+                            0 => a == s,
+                            _ => a.starts_with(&format!("{s}(")),
+                        })
                     })
-                    .unwrap();
-                let shows = nav
-                    .symbols()
-                    .filter(|(s, _)| ys.iter().any(|a| a.starts_with(s)))
                     .map(|(s, n)| format!("#show {s}/{n}."))
                     .collect::<Vec<_>>()
                     .join("\n");
-                nav.add_rule(shows.clone()).unwrap();
-                nav.add_arg("--project=show")?;
 
-                *facets = nav.sieve_quiet(&ys).unwrap();
+                // NOTE: answer sets show targets only until the rules are removed again
+                nav.add_rule(&shows)?;
+                let cc = nav.cautious_consequences(route.iter()).map(|cc| {
+                    cc.iter()
+                        .map(|a| lex::repr(*a))
+                        .collect::<std::collections::HashSet<_>>()
+                });
+                let reps = cc.map(|cc| {
+                    let ys = xs
+                        .into_iter()
+                        .filter(|x| !cc.contains(x))
+                        .collect::<Vec<_>>();
+                    let budget = Budget::new(session.timeout, ys.len());
+                    let reps =
+                        is_facet::rec_soe(nav, route, ys, Representatives::default(), &budget);
+                    budget.finish();
+                    (reps, budget.stopped_early())
+                });
+                nav.remove_rule(&shows)?;
 
-                nav.remove_rule(shows).unwrap();
+                let (reps, stopped_early) = reps.ok_or(NavigatorError::None)?;
+                session.representatives = reps.answer_sets.clone();
+                if stopped_early {
+                    println!("info: stopped early, facets are incomplete");
+                }
+                for (i, answer_set) in reps.answer_sets.iter().enumerate() {
+                    println!("representative {}:", i + 1);
+                    println!("{}", answer_set.join(" "));
+                }
+                *facets = reps.covered;
+                println!(
+                    "{} representatives {} facets",
+                    reps.answer_sets.len(),
+                    facets.len()
+                );
+                println!("soe time elapsed: {:?}", start.elapsed())
             }
             Some(IS_FACET_R) => {
                 let mut fs = vec![];
@@ -357,14 +446,88 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
                 }
             }
             Some(ENUMERATE_SOLUTIONS) => {
-                let n = nav.enumerate_solutions(
-                    split_expr
-                        .next()
-                        .and_then(|n| n.parse::<usize>().ok())
-                        .take(),
-                    route.iter().map(|s| s.as_ref()).chain(split_expr),
+                let start = Instant::now();
+                let n = split_expr.next().and_then(|n| n.parse::<usize>().ok());
+                let (mut json, mut csv, mut active) = (None, None, route.clone());
+                while let Some(arg) = split_expr.next() {
+                    match arg {
+                        "--json" => json = split_expr.next(),
+                        "--csv" => csv = split_expr.next(),
+                        _ => active.push(arg.to_owned()),
+                    }
+                }
+                let (found, answer_sets) = solutions::enumerate(
+                    &nav.program(),
+                    &session.solver_args(),
+                    &active,
+                    n,
+                    |i, atoms| {
+                        println!("solution {:?}: ", i + 1);
+                        atoms.iter().for_each(|a| print!("{a} "));
+                        println!();
+                    },
                 )?;
-                println!("found {:?}", n);
+                session.solutions = answer_sets;
+                println!("found {:?}", found);
+                if found > session.solutions.len() {
+                    println!(
+                        "info: kept the first {} answer sets",
+                        session.solutions.len()
+                    );
+                }
+
+                // NOTE: more answer sets may exist if enumeration stopped at the bound
+                let more =
+                    n.is_some_and(|n| n > 0 && found == n) || found > session.solutions.len();
+                if let Some(filename) = json {
+                    let out = solutions::to_json(&session.solutions, more, start.elapsed());
+                    if let Err(e) = std::fs::write(filename, out) {
+                        println!("error: {e}")
+                    }
+                }
+                if let Some(filename) = csv {
+                    let out = solutions::to_csv(&session.solutions, atoms);
+                    if let Err(e) = std::fs::write(filename, out) {
+                        println!("error: {e}")
+                    }
+                }
+            }
+            Some(SAMPLE_SOLUTIONS) => {
+                let (n, seed) = match (
+                    split_expr.next().and_then(|n| n.parse::<usize>().ok()),
+                    split_expr.next().and_then(|n| n.parse::<u64>().ok()),
+                ) {
+                    (Some(n), Some(seed)) => (n, seed),
+                    _ => {
+                        println!("error: specify number of answer sets and seed");
+                        return Ok(());
+                    }
+                };
+                let budget = Budget::new(session.timeout, n);
+                let mut rng = SplitMix::new(seed);
+
+                let mut found = 0;
+                for i in 0..n {
+                    if crate::interrupt::requested() {
+                        break;
+                    }
+                    let Some((_, answer_set)) =
+                        sample(session, nav, route, facets, &mut rng, &budget)
+                    else {
+                        budget.finish();
+                        println!("no answer set under route");
+                        return Ok(());
+                    };
+                    budget.tick();
+                    budget.println(format!("solution {}:", i + 1));
+                    budget.println(answer_set.join(" "));
+                    found += 1;
+                }
+                budget.finish();
+                if budget.stopped_early() {
+                    println!("info: budget exhausted, answer sets drawn approximately uniformly");
+                }
+                println!("found {:?}", found);
             }
             Some(SHOW_FACETS) => {
                 if let Some(re) = split_expr.next().and_then(|s| Regex::new(r#s).ok()) {
@@ -381,102 +544,49 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
                 println!("{:?}", 2 * facets.len())
             }
             Some(FACET_COUNTS) => {
-                let ovr_count = match self {
-                    Self::MaxWeightedFacetCounting(Some(c)) => *c,
-                    Self::MinWeightedFacetCounting(Some(c)) => *c,
-                    _ => 2 * facets.len(),
-                } as f32;
-                let mut weight = Weight::FacetCounting;
+                let ovr_count = (2 * facets.len()) as f32;
 
-                if let Some(re) = split_expr.next().and_then(|s| Regex::new(r#s).ok()) {
-                    for f in facets.iter().filter(|f| re.is_match(f)) {
-                        route.push(f.to_owned());
-                        count(&mut weight, nav, route.iter())
-                            .map(|c| println!("{:.4} {:?} {f}", c, 1.0 - (c as f32 / ovr_count)))
-                            .ok_or(NavigatorError::None)?;
-                        route.pop();
-                        route.push(format!("~{f}"));
-                        count(&mut weight, nav, route.iter())
-                            .map(|c| println!("{:.4} {:?} ~{f}", c, 1.0 - (c as f32 / ovr_count)))
-                            .ok_or(NavigatorError::None)?;
-                        route.pop();
-                    }
-                } else {
-                    for f in facets.iter() {
-                        route.push(f.to_owned());
-                        count(&mut weight, nav, route.iter())
-                            .map(|c| println!("{:.4} {:?} {f}", 1.0 - (c as f32 / ovr_count), c))
-                            .ok_or(NavigatorError::None)?;
-                        route.pop();
-                        route.push(format!("~{f}"));
-                        count(&mut weight, nav, route.iter())
-                            .map(|c| println!("{:.4} {:?} ~{f}", 1.0 - (c as f32 / ovr_count), c))
-                            .ok_or(NavigatorError::None)?;
-                        route.pop();
-                    }
-                }
-            }
-            Some(FACET_COUNTS_PROJECTING) => {
-                let ovr_count = match self {
-                    Self::MaxWeightedFacetCounting(Some(c)) => *c,
-                    Self::MinWeightedFacetCounting(Some(c)) => *c,
-                    _ => 2 * facets.len(),
-                } as f32;
-                let mut weight = Weight::FacetCounting;
-
-                let xs = if let Some(re) = split_expr.next().and_then(|s| Regex::new(r#s).ok()) {
-                    atoms
+                let fs = if let Some(re) = split_expr.next().and_then(|s| Regex::new(r#s).ok()) {
+                    facets
                         .iter()
-                        .filter(|a| re.is_match(a))
+                        .filter(|f| re.is_match(f))
                         .cloned()
                         .collect::<Vec<_>>()
                 } else {
-                    atoms.iter().cloned().collect::<Vec<_>>()
+                    facets.to_vec()
                 };
 
-                let mut or = ":-".to_owned();
-                xs.iter().for_each(|a| {
-                    or = format!("{or} not {a},");
-                });
-                or = format!("{}.", &or[..or.len() - 1]);
-
-                let shows = nav
-                    .symbols()
-                    .filter(|(s, _)| xs.iter().any(|a| a.starts_with(s)))
-                    .map(|(s, n)| format!("#show {s}/{n}."))
-                    .collect::<Vec<_>>()
-                    .join("\n");
+                let mut counter = session.counter(nav);
+                for (f, c) in counter.count_each(&Weight::FacetCounting, route, &fs) {
+                    let c = c.ok_or(NavigatorError::None)?;
+                    println!("{:.4} {:?} {f}", 1.0 - (c as f32 / ovr_count), c);
+                }
+            }
+            Some(FACET_COUNTS_PROJECTING) => {
+                let ovr_count = (2 * facets.len()) as f32;
 
-                let s = format!("{shows}\n{or}");
+                let s = projection(
+                    nav,
+                    atoms,
+                    split_expr.next().and_then(|s| Regex::new(r#s).ok()),
+                );
 
                 nav.add_rule(s.clone())?;
 
-                if let Some(re) = split_expr.next().and_then(|s| Regex::new(r#s).ok()) {
-                    for f in facets.iter().filter(|f| re.is_match(f)) {
-                        route.push(f.to_owned());
-                        count_projecting(&mut weight, nav, route.iter())
-                            .map(|c| println!("{:.4} {:?} {f}", c, 1.0 - (c as f32 / ovr_count)))
-                            .ok_or(NavigatorError::None)?;
-                        route.pop();
-                        route.push(format!("~{f}"));
-                        count_projecting(&mut weight, nav, route.iter())
-                            .map(|c| println!("{:.4} {:?} ~{f}", c, 1.0 - (c as f32 / ovr_count)))
-                            .ok_or(NavigatorError::None)?;
-                        route.pop();
-                    }
+                let fs = if let Some(re) = split_expr.next().and_then(|s| Regex::new(r#s).ok()) {
+                    facets
+                        .iter()
+                        .filter(|f| re.is_match(f))
+                        .cloned()
+                        .collect::<Vec<_>>()
                 } else {
-                    for f in facets.iter() {
-                        route.push(f.to_owned());
-                        count_projecting(&mut weight, nav, route.iter())
-                            .map(|c| println!("{:.4} {:?} {f}", 1.0 - (c as f32 / ovr_count), c))
-                            .ok_or(NavigatorError::None)?;
-                        route.pop();
-                        route.push(format!("~{f}"));
-                        count_projecting(&mut weight, nav, route.iter())
-                            .map(|c| println!("{:.4} {:?} ~{f}", 1.0 - (c as f32 / ovr_count), c))
-                            .ok_or(NavigatorError::None)?;
-                        route.pop();
-                    }
+                    facets.to_vec()
+                };
+
+                let mut counter = session.counter(nav).projecting();
+                for (f, c) in counter.count_each(&Weight::FacetCounting, route, &fs) {
+                    let c = c.ok_or(NavigatorError::None)?;
+                    println!("{:.4} {:?} {f}", 1.0 - (c as f32 / ovr_count), c);
                 }
 
                 nav.remove_rule(s)?;
@@ -492,44 +602,22 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
                 println!("{:?}", n)
             }
             Some(ANSWER_SET_COUNTS) => {
-                if let Some(re) = split_expr.next().and_then(|s| Regex::new(r#s).ok()) {
-                    let mut weight = Weight::AnswerSetCounting;
-                    let ovr_count = match self {
-                        Self::MaxWeightedAnswerSetCounting(Some(c)) => *c,
-                        Self::MinWeightedAnswerSetCounting(Some(c)) => *c,
-                        _ => count(&mut weight, nav, route.iter()).ok_or(NavigatorError::None)?,
-                    } as f32;
-                    for f in facets.iter().filter(|f| re.is_match(f)) {
-                        route.push(f.to_owned());
-                        count(&mut weight, nav, route.iter())
-                            .map(|c| println!("{:.4} {:?} {f}", 1.0 - (c as f32 / ovr_count), c))
-                            .ok_or(NavigatorError::None)?;
-                        route.pop();
-                        route.push(format!("~{f}"));
-                        count(&mut weight, nav, route.iter())
-                            .map(|c| println!("{:.4} {:?} ~{f}", 1.0 - (c as f32 / ovr_count), c))
-                            .ok_or(NavigatorError::None)?;
-                        route.pop();
-                    }
+                let fs = if let Some(re) = split_expr.next().and_then(|s| Regex::new(r#s).ok()) {
+                    facets
+                        .iter()
+                        .filter(|f| re.is_match(f))
+                        .cloned()
+                        .collect::<Vec<_>>()
                 } else {
-                    let mut weight = Weight::AnswerSetCounting;
-                    let ovr_count = match self {
-                        Self::MaxWeightedAnswerSetCounting(Some(c)) => *c,
-                        Self::MinWeightedAnswerSetCounting(Some(c)) => *c,
-                        _ => count(&mut weight, nav, route.iter()).ok_or(NavigatorError::None)?,
-                    } as f32;
-                    for f in facets.iter() {
-                        route.push(f.to_owned());
-                        count(&mut weight, nav, route.iter())
-                            .map(|c| println!("{:.4} {:?} {f}", 1.0 - (c as f32 / ovr_count), c))
-                            .ok_or(NavigatorError::None)?;
-                        route.pop();
-                        route.push(format!("~{f}"));
-                        count(&mut weight, nav, route.iter())
-                            .map(|c| println!("{:.4} {:?} ~{f}", 1.0 - (c as f32 / ovr_count), c))
-                            .ok_or(NavigatorError::None)?;
-                        route.pop();
-                    }
+                    facets.to_vec()
+                };
+
+                let weight = Weight::AnswerSetCounting;
+                let mut counter = session.counter(nav);
+                let ovr_count = counter.count(&weight, route).ok_or(NavigatorError::None)? as f32;
+                for (f, c) in counter.count_each(&weight, route, &fs) {
+                    let c = c.ok_or(NavigatorError::None)?;
+                    println!("{:.4} {:?} {f}", 1.0 - (c as f32 / ovr_count), c);
                 }
             }
             Some(SHOW_ROUTE) => {
@@ -542,68 +630,463 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
             }
             Some(DEL_LAST) => {
                 route.pop();
-                *facets = nav
-                    .facet_inducing_atoms(route.iter())
-                    .ok_or(NavigatorError::None)?
-                    .iter()
-                    .map(|f| lex::repr(*f))
-                    .collect();
+                *facets = session
+                    .history
+                    .facets(nav, route)
+                    .ok_or(NavigatorError::None)?;
             }
             Some(CLEAR_ROUTE) => {
                 route.clear();
-                *facets = nav
-                    .facet_inducing_atoms(route.iter())
-                    .ok_or(NavigatorError::None)?
-                    .iter()
-                    .map(|f| lex::repr(*f))
-                    .collect();
+                *facets = session
+                    .history
+                    .facets(nav, route)
+                    .ok_or(NavigatorError::None)?;
             }
-            Some(DISPLAY_MODE) => println!("{}", self),
-            Some(CHANGE_MODE) => match split_expr.next() {
-                Some("min#f") => {
-                    *self = Mode::MinWeightedFacetCounting(
-                        split_expr
-                            .next()
-                            .and_then(|n| n.parse::<usize>().ok())
-                            .take(),
+            Some(DISPLAY_MODE) => match &session.projection {
+                Some(re) => println!("{} projecting onto {re}", self),
+                _ => println!("{}", self),
+            },
+            Some(PROJECT) => match split_expr.next() {
+                Some("off") => session.projection = None,
+                Some(re) if Regex::new(re).is_ok() => session.projection = Some(re.to_owned()),
+                Some(_) => println!("error: invalid regex"),
+                None => match &session.projection {
+                    Some(re) => println!("{re}"),
+                    _ => println!("none"),
+                },
+            },
+            Some(CHECK_FACETS) => match split_expr.next() {
+                Some("on") => session.history.check = true,
+                Some("off") => session.history.check = false,
+                _ => println!("{}", session.history.check),
+            },
+            Some(CACHE) => match split_expr.next() {
+                Some("stats") => println!("{}", session.cache.stats()),
+                Some("clear") => session.cache.clear(),
+                _ => println!("error: specify {{stats, clear}}"),
+            },
+            Some(SAMPLE) => {
+                let (n, seed) = match (
+                    split_expr.next().and_then(|n| n.parse::<usize>().ok()),
+                    split_expr.next().and_then(|n| n.parse::<usize>().ok()),
+                ) {
+                    (Some(n), Some(seed)) => (n, seed),
+                    _ => {
+                        println!("error: specify number of samples and seed");
+                        return Ok(());
+                    }
+                };
+                let budget = Budget::new(session.timeout, n);
+                let ties = session.ties.clone();
+
+                for i in 0..n {
+                    if budget.exhausted() {
+                        break;
+                    }
+                    let mut mode = Mode::Random(Some(seed.wrapping_add(i)));
+                    let mut walk = route.clone();
+                    let mut fs = facets.clone();
+                    while perform_next_step(
+                        &mut mode,
+                        &mut session.counter(nav),
+                        &mut walk,
+                        &fs,
+                        &ties,
+                        &budget,
                     )
+                    .is_some()
+                    {
+                        fs = session
+                            .history
+                            .facets(nav, &walk)
+                            .ok_or(NavigatorError::None)?;
+                    }
+                    budget.tick();
+
+                    budget.println(format!("sample {}:", i + 1));
+                    budget.println(walk.join(" "));
+                    match nav.one_or_none(walk.iter()) {
+                        Some(answer_set) => budget.println(answer_set.join(" ")),
+                        _ => budget.println("unsatisfiable"),
+                    }
                 }
-                Some("max#f") => {
-                    *self = Mode::MaxWeightedFacetCounting(
-                        split_expr
-                            .next()
-                            .and_then(|n| n.parse::<usize>().ok())
-                            .take(),
-                    )
+                budget.finish();
+                if budget.stopped_early() {
+                    println!("info: stopped early");
                 }
-                Some("min#a") => {
-                    *self = Mode::MinWeightedAnswerSetCounting(
-                        split_expr
-                            .next()
-                            .and_then(|n| n.parse::<usize>().ok())
-                            .take(),
-                    )
+            }
+            Some(WEIGHTS) => match split_expr.next() {
+                Some("use") => match split_expr.next() {
+                    Some(name) if session.weights.select(name) => (),
+                    _ => println!("error: unknown weight"),
+                },
+                Some("load") => match (split_expr.next(), split_expr.next()) {
+                    (Some(name), Some(filename)) => {
+                        if session.weights.load(name, filename).is_none() {
+                            println!("error: could not read weights")
+                        }
+                    }
+                    _ => println!("error: specify name and filename"),
+                },
+                Some(_) => println!("error: specify {{use, load}}"),
+                None => session.weights.names().for_each(|name| {
+                    match name == session.weights.active_name() {
+                        true => println!("{name} *"),
+                        _ => println!("{name}"),
+                    }
+                }),
+            },
+            Some(WEIGH) => {
+                let fs = if let Some(re) = split_expr.next().and_then(|s| Regex::new(r#s).ok()) {
+                    facets
+                        .iter()
+                        .filter(|f| re.is_match(f))
+                        .cloned()
+                        .collect::<Vec<_>>()
+                } else {
+                    facets.to_vec()
+                };
+                let mut counter = session.counter(nav);
+                let ovr_weight = counter.weigh(route).ok_or(NavigatorError::None)?;
+                let lits = fs
+                    .iter()
+                    .flat_map(|f| [f.clone(), format!("~{f}")])
+                    .collect::<Vec<_>>();
+                let routes = lits
+                    .iter()
+                    .map(|l| route.iter().chain([l]).cloned().collect::<Vec<_>>())
+                    .collect::<Vec<_>>();
+                for (l, w) in lits.iter().zip(counter.weigh_all(&routes)) {
+                    let w = w.ok_or(NavigatorError::None)?;
+                    println!("{:.4} {w:.4} {l}", 1.0 - w / ovr_weight);
                 }
-                Some("max#a") => {
-                    *self = Mode::MaxWeightedAnswerSetCounting(
-                        split_expr
-                            .next()
-                            .and_then(|n| n.parse::<usize>().ok())
-                            .take(),
-                    )
+            }
+            Some(OPTIMALITY) => {
+                let optimality = match split_expr.next() {
+                    Some("optimal") => match Optimality::optimal(&nav.program(), &session.args) {
+                        Some(optimality) => optimality,
+                        _ => {
+                            println!("error: no answer set");
+                            return Ok(());
+                        }
+                    },
+                    Some("bound") => match split_expr
+                        .next()
+                        .map(|b| b.split(',').map(|c| c.parse::<i64>()).collect())
+                    {
+                        Some(Ok(costs)) => Optimality::Bounded(costs),
+                        _ => {
+                            println!("error: specify bound, e.g., 3 or 3,1 for several priorities");
+                            return Ok(());
+                        }
+                    },
+                    Some("off") => Optimality::All,
+                    Some(_) => {
+                        println!("error: specify {{optimal, bound, off}}");
+                        return Ok(());
+                    }
+                    None => {
+                        println!("{}", session.optimality);
+                        return Ok(());
+                    }
+                };
+                session.optimality = optimality;
+                *nav = Navigator::new(nav.program(), session.solver_args())?;
+                session.history.clear();
+                *facets = session
+                    .history
+                    .facets(nav, route)
+                    .ok_or(NavigatorError::None)?;
+                println!("{}", session.optimality);
+            }
+            Some(COSTS) => {
+                let fs = if let Some(re) = split_expr.next().and_then(|s| Regex::new(r#s).ok()) {
+                    facets
+                        .iter()
+                        .filter(|f| re.is_match(f))
+                        .cloned()
+                        .collect::<Vec<_>>()
+                } else {
+                    facets.to_vec()
+                };
+                let budget = Budget::new(session.timeout, fs.len());
+                let program = nav.program();
+                for f in fs {
+                    if budget.exhausted() {
+                        break;
+                    }
+                    for l in [f.clone(), format!("~{f}")] {
+                        let mut delta = route.clone();
+                        delta.push(l.clone());
+                        match optimum(&program, &session.args, &delta) {
+                            Some(costs) => budget.println(format!("{costs:?} {l}")),
+                            _ => budget.println(format!("_ {l}")),
+                        }
+                    }
+                    budget.tick();
                 }
-                Some("go") => {
-                    *self = Mode::GoalOriented(
-                        split_expr
-                            .next()
-                            .and_then(|n| n.parse::<usize>().ok())
-                            .take(),
-                    )
+                budget.finish();
+                if budget.stopped_early() {
+                    println!("info: stopped early");
+                }
+            }
+            Some(AUTO) => {
+                let start = Instant::now();
+                let (mut fc_bound, mut as_bound, mut goal, mut deadline) = (None, None, None, None);
+                while let Some(criterion) = split_expr.next() {
+                    let arg = split_expr.next();
+                    match (criterion, arg) {
+                        ("fc", Some(k)) => fc_bound = k.parse::<usize>().ok(),
+                        ("as", Some(k)) => as_bound = k.parse::<usize>().ok(),
+                        ("goal", Some(l)) => goal = Some(l.to_owned()),
+                        ("time", Some(t)) => {
+                            deadline = t
+                                .parse::<f64>()
+                                .ok()
+                                .map(|t| start + Duration::from_secs_f64(t))
+                        }
+                        _ => {
+                            println!("error: specify criteria among {{fc k, as k, goal literal, time secs}}");
+                            return Ok(());
+                        }
+                    }
+                }
+                if let Some(g) = &goal {
+                    if !nav.atoms().any(|a| a == g.trim_start_matches('~')) {
+                        println!("error: unknown atom in goal");
+                        return Ok(());
+                    }
+                }
+
+                let mut steps: Vec<(String, usize, Option<usize>)> = vec![];
+                let reason = loop {
+                    // NOTE: enumeration stops once the bound is exceeded
+                    let as_count = match as_bound {
+                        Some(k) => {
+                            Some(nav.enumerate_solutions_quietly(Some(k + 1), route.iter())?)
+                        }
+                        _ => None,
+                    };
+                    // NOTE: counts after the previous step
+                    if let Some((_, _, c)) = steps.last_mut() {
+                        *c = as_count;
+                    }
+
+                    if facets.is_empty() {
+                        break "no facets left";
+                    }
+                    if fc_bound.is_some_and(|k| 2 * facets.len() <= k) {
+                        break "facet count bound reached";
+                    }
+                    if as_bound.zip(as_count).is_some_and(|(k, c)| c <= k) {
+                        break "answer set count bound reached";
+                    }
+                    if let Some(g) = &goal {
+                        let a = g.trim_start_matches('~');
+                        let brave = nav
+                            .brave_consequences(route.iter())
+                            .ok_or(NavigatorError::None)?
+                            .iter()
+                            .any(|x| lex::repr(*x) == a);
+                        let cautious = nav
+                            .cautious_consequences(route.iter())
+                            .ok_or(NavigatorError::None)?
+                            .iter()
+                            .any(|x| lex::repr(*x) == a);
+                        // NOTE: a is reached once cautious, ~a once not brave
+                        match (g.starts_with('~'), brave, cautious) {
+                            (false, _, true) | (true, false, _) => break "goal reached",
+                            (false, false, _) | (true, _, true) => break "goal unreachable",
+                            _ => (),
+                        }
+                    }
+                    if deadline.is_some_and(|d| Instant::now() >= d) {
+                        break "time budget exhausted";
+                    }
+                    if crate::interrupt::requested() {
+                        break "interrupted";
+                    }
+
+                    let n = route.len();
+                    self.command(
+                        TAKE_STEP.to_owned(),
+                        nav,
+                        atoms,
+                        facets,
+                        route,
+                        ctx,
+                        session,
+                    )?;
+                    match route.len() > n {
+                        true => steps.push((route[n].clone(), 2 * facets.len(), None)),
+                        _ => break "no step",
+                    }
+                };
+
+                println!("stopped: {reason}");
+                for (i, (l, fc, as_count)) in steps.iter().enumerate() {
+                    match as_count.zip(as_bound) {
+                        Some((c, k)) if c > k => println!("{} {fc} >{k} {l}", i + 1),
+                        Some((c, _)) => println!("{} {fc} {c} {l}", i + 1),
+                        _ => println!("{} {fc} _ {l}", i + 1),
+                    }
+                }
+                println!("steps {} time elapsed: {:?}", steps.len(), start.elapsed())
+            }
+            Some(BENCHMARK_MODES) => {
+                let weight = session.weights.active_name();
+                let modes = [
+                    ("go".to_owned(), Mode::GoalOriented(None)),
+                    ("max#f".to_owned(), Mode::MaxWeightedFacetCounting(None)),
+                    ("min#f".to_owned(), Mode::MinWeightedFacetCounting(None)),
+                    ("max#a".to_owned(), Mode::MaxWeightedAnswerSetCounting(None)),
+                    ("min#a".to_owned(), Mode::MinWeightedAnswerSetCounting(None)),
+                    (format!("max#w[{weight}]"), Mode::MaxWeighted(None)),
+                    (format!("min#w[{weight}]"), Mode::MinWeighted(None)),
+                    ("random[0]".to_owned(), Mode::Random(Some(0))),
+                ];
+                let ties = session.ties.clone();
+
+                println!("mode steps routes_counted time answer_set");
+                for (label, mut mode) in modes {
+                    if crate::interrupt::requested() {
+                        break;
+                    }
+                    let start = Instant::now();
+                    let mut history = History::default();
+                    let (mut walk, mut fs) = (route.clone(), facets.clone());
+                    let (mut steps, mut counted) = (0, 0);
+                    loop {
+                        let budget = Budget::quiet(session.timeout);
+                        let mut counter = session.uncached_counter(nav);
+                        let step = perform_next_step(
+                            &mut mode,
+                            &mut counter,
+                            &mut walk,
+                            &fs,
+                            &ties,
+                            &budget,
+                        );
+                        counted += counter.counted();
+                        match step {
+                            Some((_, c)) => mode.update(c),
+                            _ => break,
+                        }
+                        steps += 1;
+                        fs = history.facets(nav, &walk).ok_or(NavigatorError::None)?;
+                        if crate::interrupt::requested() {
+                            break;
+                        }
+                    }
+
+                    let answer_set = nav
+                        .one_or_none(walk.iter())
+                        .map(|xs| xs.join(" "))
+                        .unwrap_or("_".to_owned());
+                    println!(
+                        "{label} {steps} {counted} {:?} {answer_set}",
+                        start.elapsed()
+                    );
+                }
+            }
+            Some(TIE_BREAK) => match split_expr.next() {
+                Some("solver") => session.ties = TieBreak::Solver,
+                Some("lex") => session.ties = TieBreak::Lexicographic,
+                Some("priority") => {
+                    session.ties = TieBreak::Priority(split_expr.map(|p| p.to_owned()).collect())
+                }
+                Some("weights") => match split_expr.next().and_then(TieBreak::from_weights) {
+                    Some(ties) => session.ties = ties,
+                    _ => println!("error: could not read weights"),
+                },
+                Some("random") => match split_expr.next().map(|n| n.parse::<u64>()) {
+                    Some(Ok(seed)) => session.ties = TieBreak::Random(seed),
+                    _ => println!("error: specify seed"),
+                },
+                Some(_) => println!("error: specify {{solver, lex, priority, weights, random}}"),
+                None => println!("{}", session.ties),
+            },
+            Some(TIMEOUT) => match split_expr.next().map(|t| t.parse::<f64>()) {
+                Some(Ok(secs)) if secs > 0.0 => {
+                    session.timeout = Some(Duration::from_secs_f64(secs))
                 }
-                _ => println!("error: specify mode among {{{{min,max}}#{{f,a,s}}, go}}"),
+                Some(Ok(_)) => session.timeout = None,
+                Some(Err(_)) => println!("error: specify timeout in seconds"),
+                None => match session.timeout {
+                    Some(t) => println!("{:?}", t),
+                    None => println!("none"),
+                },
             },
+            Some(CHANGE_MODE) => {
+                match split_expr.next() {
+                    Some("min#f") => {
+                        *self = Mode::MinWeightedFacetCounting(
+                            split_expr
+                                .next()
+                                .and_then(|n| n.parse::<usize>().ok())
+                                .take(),
+                        )
+                    }
+                    Some("max#f") => {
+                        *self = Mode::MaxWeightedFacetCounting(
+                            split_expr
+                                .next()
+                                .and_then(|n| n.parse::<usize>().ok())
+                                .take(),
+                        )
+                    }
+                    Some("min#a") => {
+                        *self = Mode::MinWeightedAnswerSetCounting(
+                            split_expr
+                                .next()
+                                .and_then(|n| n.parse::<usize>().ok())
+                                .take(),
+                        )
+                    }
+                    Some("max#a") => {
+                        *self = Mode::MaxWeightedAnswerSetCounting(
+                            split_expr
+                                .next()
+                                .and_then(|n| n.parse::<usize>().ok())
+                                .take(),
+                        )
+                    }
+                    Some("go") => {
+                        *self = Mode::GoalOriented(
+                            split_expr
+                                .next()
+                                .and_then(|n| n.parse::<usize>().ok())
+                                .take(),
+                        )
+                    }
+                    Some("opt") => {
+                        session.weights.select_temporarily("cost");
+                        *self = Mode::MaxWeighted(None);
+                        return Ok(());
+                    }
+                    Some("min#w") => *self = Mode::MinWeighted(None),
+                    Some("max#w") => *self = Mode::MaxWeighted(None),
+                    Some("random") => {
+                        *self =
+                            Mode::Random(split_expr.next().and_then(|n| n.parse::<usize>().ok()))
+                    }
+                    _ => {
+                        println!("error: specify mode among {{{{min,max}}#{{f,a,s}}, {{min,max}}#w, opt, go, random}}");
+                        return Ok(());
+                    }
+                }
+                // NOTE: the cost weight of opt is active until the mode changes
+                session.weights.restore();
+            }
             Some(PROPOSE_STEP) => {
-                let fs = if let Some(re) = split_expr.next().and_then(|s| Regex::new(r#s).ok()) {
+                let (k, re) = match split_expr.next() {
+                    Some(s) => match s.parse::<usize>() {
+                        Ok(k) => (Some(k), split_expr.next()),
+                        _ => (None, Some(s)),
+                    },
+                    _ => (None, None),
+                };
+                let fs = if let Some(re) = re.and_then(|s| Regex::new(r#s).ok()) {
                     facets
                         .iter()
                         .filter(|f| re.is_match(f))
@@ -612,7 +1095,63 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
                 } else {
                     facets.to_vec()
                 };
-                match propose_next_step(self, nav, route, &fs) {
+                let budget = Budget::new(session.timeout, fs.len());
+                let ties = session.ties.clone();
+                let projecting = session.projection.is_some();
+                let mut counter = session.counter(nav);
+                counter.projecting = projecting;
+
+                if let Mode::MinWeighted(_) | Mode::MaxWeighted(_) = self {
+                    let ovr_weight = counter.weigh(route).ok_or(NavigatorError::None)?;
+                    let ranked = rank_weighted(self, &mut counter, route, &fs, &ties, &budget);
+                    budget.finish();
+                    if budget.stopped_early() {
+                        println!("info: stopped early, ranked candidates so far");
+                    }
+                    match (k, ranked.first()) {
+                        (_, None) => println!("noop"),
+                        (None, Some((f, w))) => println!("{f} {w:.4}"),
+                        (Some(k), _) => {
+                            for (i, (f, w)) in ranked.iter().take(k).enumerate() {
+                                println!("{} {:.4} {w:.4} {f}", i + 1, 1.0 - w / ovr_weight)
+                            }
+                        }
+                    }
+                    return Ok(());
+                }
+
+                if let Some(k) = k {
+                    let ovr_count = self
+                        .reference_count(&mut counter, route, facets)
+                        .ok_or(NavigatorError::None)? as f32;
+                    let ranked = self.rank_facets_within(&mut counter, route, &fs, &ties, &budget);
+                    budget.finish();
+                    if budget.stopped_early() {
+                        println!("info: stopped early, ranked candidates so far");
+                    }
+                    if ranked.is_empty() {
+                        println!("noop");
+                    }
+                    for (i, (f, c)) in ranked.iter().take(k).enumerate() {
+                        match c {
+                            Some(c) => println!(
+                                "{} {:.4} {:?} {f}",
+                                i + 1,
+                                1.0 - (*c as f32 / ovr_count),
+                                c
+                            ),
+                            _ => println!("{} _ _ {f}", i + 1),
+                        }
+                    }
+                    return Ok(());
+                }
+
+                let step = propose_next_step(self, &mut counter, route, &fs, &ties, &budget);
+                budget.finish();
+                if budget.stopped_early() {
+                    println!("info: stopped early, best candidate so far");
+                }
+                match step {
                     Some((f, Some(c))) => println!("{f} {:?}", c),
                     Some((f, None)) => println!("{f} _"),
                     _ => println!("noop"),
@@ -620,7 +1159,14 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
             }
             Some(TAKE_STEP) => {
                 let start = Instant::now();
-                let fs = if let Some(re) = split_expr.next().and_then(|s| Regex::new(r#s).ok()) {
+                let (i, re) = match split_expr.next() {
+                    Some(s) => match s.parse::<usize>() {
+                        Ok(i) => (Some(i), split_expr.next()),
+                        _ => (None, Some(s)),
+                    },
+                    _ => (None, None),
+                };
+                let fs = if let Some(re) = re.and_then(|s| Regex::new(r#s).ok()) {
                     facets
                         .iter()
                         .filter(|f| re.is_match(f))
@@ -629,41 +1175,65 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
                 } else {
                     facets.to_vec()
                 };
-                let ovr_count = match self {
-                    Self::MaxWeightedFacetCounting(Some(c)) => *c,
-                    Self::MinWeightedFacetCounting(Some(c)) => *c,
-                    Self::MaxWeightedFacetCounting(None) | Self::MinWeightedFacetCounting(None) => {
-                        2 * facets.len()
-                    }
-                    Self::MaxWeightedAnswerSetCounting(Some(c)) => *c,
-                    Self::MinWeightedAnswerSetCounting(Some(c)) => *c,
-                    Self::MaxWeightedAnswerSetCounting(None)
-                    | Self::MinWeightedAnswerSetCounting(None) => {
-                        count(&mut Weight::AnswerSetCounting, nav, route.iter())
-                            .ok_or(NavigatorError::None)?
+                let budget = Budget::new(session.timeout, fs.len());
+                let ties = session.ties.clone();
+                let projecting = session.projection.is_some();
+                let mut counter = session.counter(nav);
+                counter.projecting = projecting;
+
+                if let Mode::MinWeighted(_) | Mode::MaxWeighted(_) = self {
+                    let ovr_weight = counter.weigh(route).ok_or(NavigatorError::None)?;
+                    let ranked = rank_weighted(self, &mut counter, route, &fs, &ties, &budget);
+                    budget.finish();
+                    if budget.stopped_early() {
+                        println!("info: stopped early, took best candidate so far");
                     }
-                    Self::GoalOriented(_) => usize::default(),
-                } as f32;
+                    match i
+                        .unwrap_or(1)
+                        .checked_sub(1)
+                        .and_then(|i| ranked.into_iter().nth(i))
+                    {
+                        Some((f, w)) => {
+                            println!("{:.4} {w:.4} {f}", 1.0 - w / ovr_weight);
+                            route.push(f);
+                            *facets = session
+                                .history
+                                .facets(nav, route)
+                                .ok_or(NavigatorError::None)?;
+                        }
+                        _ => println!("noop"),
+                    }
+                    println!("tak time elapsed: {:?}", start.elapsed());
+                    return Ok(());
+                }
+
+                let ovr_count = self
+                    .reference_count(&mut counter, route, facets)
+                    .ok_or(NavigatorError::None)? as f32;
 
-                match perform_next_step(self, nav, route, &fs) {
+                let step = match i {
+                    Some(i) => perform_nth_step(self, &mut counter, route, &fs, i, &ties, &budget),
+                    _ => perform_next_step(self, &mut counter, route, &fs, &ties, &budget),
+                };
+                budget.finish();
+                if budget.stopped_early() {
+                    println!("info: stopped early, took best candidate so far");
+                }
+                match step {
                     Some((f, Some(c))) => {
                         println!("{:.4} {:?} {f}", 1.0 - (c as f32 / ovr_count), c);
                         self.update(Some(c));
-                        *facets = nav
-                            .facet_inducing_atoms(route.iter())
-                            .ok_or(NavigatorError::None)?
-                            .iter()
-                            .map(|f| lex::repr(*f))
-                            .collect();
+                        *facets = session
+                            .history
+                            .facets(nav, route)
+                            .ok_or(NavigatorError::None)?;
                     }
                     Some((f, None)) => {
                         println!("_ _ {f}");
-                        *facets = nav
-                            .facet_inducing_atoms(route.iter())
-                            .ok_or(NavigatorError::None)?
-                            .iter()
-                            .map(|f| lex::repr(*f))
-                            .collect();
+                        *facets = session
+                            .history
+                            .facets(nav, route)
+                            .ok_or(NavigatorError::None)?;
                     }
                     _ => println!("noop"),
                 }
@@ -704,6 +1274,7 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
                                             facets,
                                             route,
                                             ctx,
+                                            session,
                                         )?
                                     }
                                 }
@@ -724,6 +1295,7 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
                                             facets,
                                             route,
                                             ctx,
+                                            session,
                                         )?
                                     }
                                 }
@@ -750,6 +1322,7 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
                                             facets,
                                             route,
                                             ctx,
+                                            session,
                                         )?
                                     }
                                 }
@@ -770,6 +1343,7 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
                                             facets,
                                             route,
                                             ctx,
+                                            session,
                                         )?
                                     }
                                 }
@@ -796,6 +1370,7 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
                                             facets,
                                             route,
                                             ctx,
+                                            session,
                                         )?
                                     }
                                 }
@@ -816,6 +1391,7 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
                                             facets,
                                             route,
                                             ctx,
+                                            session,
                                         )?
                                     }
                                 }
@@ -842,6 +1418,7 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
                                             facets,
                                             route,
                                             ctx,
+                                            session,
                                         )?
                                     }
                                 }
@@ -862,6 +1439,7 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
                                             facets,
                                             route,
                                             ctx,
+                                            session,
                                         )?
                                     }
                                 }
@@ -888,6 +1466,7 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
                                             facets,
                                             route,
                                             ctx,
+                                            session,
                                         )?
                                     }
                                 }
@@ -908,6 +1487,7 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
                                             facets,
                                             route,
                                             ctx,
+                                            session,
                                         )?
                                     }
                                 }
@@ -957,6 +1537,7 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
                 println!("{}", nav.program());
             }
             Some(SOE) => {
+                let start = Instant::now();
                 let fs = if let Some(re) = split_expr.next().and_then(|s| Regex::new(r#s).ok()) {
                     facets
                         .iter()
@@ -966,7 +1547,45 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
                 } else {
                     facets.to_vec()
                 };
-                nav.sieve(&fs)?;
+                let mut options = split_expr.peekable();
+                // NOTE: sieve of savan unless options ask for diversity
+                if options.peek().is_none() {
+                    session.representatives.clear();
+                    nav.sieve(&fs)?;
+                    return Ok(());
+                }
+                let diversity = match Diversity::parse(options) {
+                    Some(diversity) => diversity,
+                    _ => {
+                        println!("error: specify options among {{max k, dist d, greedy}}");
+                        return Ok(());
+                    }
+                };
+
+                let budget = Budget::new(session.timeout, fs.len());
+                let reps = is_facet::diverse_soe(nav, route, &fs, &diversity, &budget);
+                session.representatives = reps.answer_sets.clone();
+                budget.finish();
+                if budget.stopped_early() {
+                    println!("info: stopped early");
+                }
+
+                for (i, (answer_set, covers)) in
+                    reps.answer_sets.iter().zip(&reps.covers).enumerate()
+                {
+                    println!("solution {}:", i + 1);
+                    println!("{}", answer_set.join(" "));
+                    println!("covers {}", covers.join(" "));
+                }
+                let uncovered = fs
+                    .iter()
+                    .filter(|f| !reps.covered.contains(f))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if !uncovered.is_empty() {
+                    println!("uncovered {}", uncovered.join(" "));
+                }
+                println!("soe time elapsed: {:?}", start.elapsed())
             }
             Some(CONTEXT) => {
                 ctx.into_iter()
@@ -1000,68 +1619,322 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
                     _ => (),
                 };
 
-                *facets = nav
-                    .facet_inducing_atoms(route.iter())
-                    .ok_or(NavigatorError::None)?
-                    .iter()
-                    .map(|f| lex::repr(*f))
-                    .collect();
+                session.history.clear();
+                *facets = session
+                    .history
+                    .facets(nav, route)
+                    .ok_or(NavigatorError::None)?;
             }
-            Some(SIGNIFICANCE) => {
+            Some(cmd @ (SIGNIFICANCE | SIGNIFICANCE_ANSWER_SETS)) => {
                 let start = Instant::now();
-                let y = split_expr.next().unwrap();
-                if let Some(re) = split_expr.next().and_then(|s| Regex::new(r#s).ok()) {
-                    nav.significance(&route, y.to_owned(), &facets, re)
-                }
+                let (y, re) = match (
+                    split_expr.next(),
+                    split_expr.next().and_then(|s| Regex::new(r#s).ok()),
+                ) {
+                    (Some(y), Some(re)) => (y, re),
+                    _ => {
+                        println!("error: specify literal and facets");
+                        return Ok(());
+                    }
+                };
+                let selection = match Selection::parse(split_expr) {
+                    Some(selection) => selection,
+                    _ => {
+                        println!(
+                            "error: specify options among {{sort inc|exc, top k, inc t, exc t}}"
+                        );
+                        return Ok(());
+                    }
+                };
+
+                let mut counter = session.counter(nav);
+                let rows = match cmd {
+                    SIGNIFICANCE_ANSWER_SETS => {
+                        counter.answer_set_significance(route, y, facets, &re)?
+                    }
+                    _ => counter.significance(route, y, facets, &re)?,
+                };
+                print_significance(nav, route, y, &selection.apply(rows))?;
                 println!("sig time elapsed: {:?}", start.elapsed())
             }
-            Some(SIGNIFICANCE_PROJECTING) => {
-                let y = split_expr.next().unwrap();
+            Some(SIGNIFICANCE_MATRIX) => {
+                let start = Instant::now();
+                let (ys, re) = match (
+                    split_expr.next().and_then(|s| Regex::new(r#s).ok()),
+                    split_expr.next().and_then(|s| Regex::new(r#s).ok()),
+                ) {
+                    (Some(ys), Some(re)) => (ys, re),
+                    _ => {
+                        println!("error: specify literals and facets");
+                        return Ok(());
+                    }
+                };
+                let literals = facets
+                    .iter()
+                    .flat_map(|f| [f.clone(), format!("~{f}")])
+                    .filter(|l| ys.is_match(l))
+                    .collect::<Vec<_>>();
+                let fs = facets
+                    .iter()
+                    .filter(|f| re.is_match(f))
+                    .cloned()
+                    .collect::<Vec<_>>();
 
-                let xs = if let Some(re) = split_expr.next().and_then(|s| Regex::new(r#s).ok()) {
-                    atoms
+                let matrix = session
+                    .counter(nav)
+                    .significance_matrix(route, &literals, &fs, &re)?;
+                let csv = matrix_to_csv(&fs, &matrix);
+                match split_expr.next() {
+                    Some(filename) => {
+                        if let Err(e) = std::fs::write(filename, csv) {
+                            println!("error: {e}")
+                        }
+                    }
+                    _ => print!("{csv}"),
+                }
+                println!("sig time elapsed: {:?}", start.elapsed())
+            }
+            Some(IMPLICATIONS) => {
+                let start = Instant::now();
+                let fs = match split_expr.next().and_then(|s| Regex::new(r#s).ok()) {
+                    Some(re) => facets
                         .iter()
-                        .filter(|a| re.is_match(a))
+                        .filter(|f| re.is_match(f))
                         .cloned()
-                        .collect::<Vec<_>>()
-                } else {
-                    atoms.iter().cloned().collect::<Vec<_>>()
+                        .collect::<Vec<_>>(),
+                    _ => facets.to_vec(),
                 };
 
-                let mut or = ":-".to_owned();
-                xs.iter().for_each(|a| {
-                    or = format!("{or} not {a},");
-                });
-                or = format!("{}.", &or[..or.len() - 1]);
+                let budget = Budget::new(session.timeout, fs.len());
+                let implications =
+                    Implications::mine(nav, route, &fs, &budget).ok_or(NavigatorError::None)?;
+                budget.finish();
+                if budget.stopped_early() {
+                    println!("info: stopped early, implications are incomplete");
+                }
 
-                let shows = nav
-                    .symbols()
-                    .filter(|(s, _)| xs.iter().any(|a| a.starts_with(s)))
-                    .map(|(s, n)| format!("#show {s}/{n}."))
-                    .collect::<Vec<_>>()
-                    .join("\n");
+                for (a, relation, b) in &implications.edges {
+                    println!("{a} {relation} {b}");
+                }
+                if let Some(filename) = split_expr.next() {
+                    if let Err(e) = std::fs::write(filename, implications.to_dot()) {
+                        println!("error: {e}")
+                    }
+                }
+                println!("imp time elapsed: {:?}", start.elapsed())
+            }
+            Some(GRAPH) => {
+                let start = Instant::now();
+                let (format, filename) = match (split_expr.next(), split_expr.next()) {
+                    (Some(format @ ("dot" | "graphml")), Some(filename)) => (format, filename),
+                    _ => {
+                        println!("error: specify format among {{dot, graphml}} and filename");
+                        return Ok(());
+                    }
+                };
+                let fs = match split_expr.next().and_then(|s| Regex::new(r#s).ok()) {
+                    Some(re) => facets
+                        .iter()
+                        .filter(|f| re.is_match(f))
+                        .cloned()
+                        .collect::<Vec<_>>(),
+                    _ => facets.to_vec(),
+                };
 
-                let s = format!("{shows}\n{or}");
+                let budget = Budget::new(session.timeout, fs.len());
+                let mut implications =
+                    Implications::mine(nav, route, &fs, &budget).ok_or(NavigatorError::None)?;
+                budget.finish();
+                if budget.stopped_early() {
+                    println!("info: stopped early, edges are incomplete");
+                }
+                implications.annotate(&mut session.counter(nav), route);
 
-                nav.add_rule(s.clone())?;
+                let graph = match format {
+                    "dot" => implications.to_dot(),
+                    _ => implications.to_graphml(),
+                };
+                if let Err(e) = std::fs::write(filename, graph) {
+                    println!("error: {e}");
+                    return Ok(());
+                }
+                println!(
+                    "{} nodes {} edges",
+                    implications.facets.len(),
+                    implications.edges.len()
+                );
+                println!("graph time elapsed: {:?}", start.elapsed())
+            }
+            Some(DIFF) => {
+                let (i, j) = match (split_expr.next(), split_expr.next()) {
+                    (Some(i), Some(j)) => (i, j),
+                    _ => {
+                        println!("error: specify two answer sets, e.g., 1 2 or 1 r2");
+                        return Ok(());
+                    }
+                };
+                let (x, y) = match (session.answer_set(i), session.answer_set(j)) {
+                    (Some(x), Some(y)) => (x, y),
+                    _ => {
+                        println!("error: unknown answer set");
+                        return Ok(());
+                    }
+                };
 
-                if let Some(re) = split_expr.next().and_then(|s| Regex::new(r#s).ok()) {
-                    nav.significance_projecting(&route, y.to_owned(), &facets, re)
+                let shown = atoms.iter().collect::<std::collections::HashSet<_>>();
+                let (only_x, only_y, common) = solutions::diff(x, y);
+                for (label, xs) in [
+                    (format!("only {i}"), only_x),
+                    (format!("only {j}"), only_y),
+                    ("common".to_owned(), common),
+                ] {
+                    let xs = xs
+                        .into_iter()
+                        .filter(|a| shown.contains(a))
+                        .collect::<Vec<_>>();
+                    println!("{label}: {}", xs.join(" "));
                 }
+            }
+            Some(CLUSTER) => {
+                let mut arg = split_expr.next();
+                let (answer_sets, prefix) = match arg {
+                    Some("r") => {
+                        arg = split_expr.next();
+                        (&session.representatives, "r")
+                    }
+                    _ => (&session.solutions, ""),
+                };
+                let threshold = match arg.map(|t| t.parse::<f64>()) {
+                    Some(Ok(t)) => t,
+                    Some(_) => {
+                        println!("error: specify similarity between 0 and 1");
+                        return Ok(());
+                    }
+                    _ => 0.5,
+                };
 
+                let shown = atoms.iter().collect::<std::collections::HashSet<_>>();
+                for (k, cluster) in solutions::cluster(answer_sets, threshold)
+                    .iter()
+                    .enumerate()
+                {
+                    let ids = cluster
+                        .iter()
+                        .map(|i| format!("{prefix}{}", i + 1))
+                        .collect::<Vec<_>>();
+                    let common = answer_sets[cluster[0]]
+                        .iter()
+                        .filter(|a| shown.contains(a))
+                        .filter(|a| cluster.iter().all(|i| answer_sets[*i].contains(a)))
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    println!("cluster {}: {}", k + 1, ids.join(" "));
+                    println!("common: {}", common.join(" "));
+                }
+            }
+            Some(SIGNIFICANCE_PROJECTING) => {
+                let (y, targets, re) = match (
+                    split_expr.next(),
+                    split_expr.next().and_then(|s| Regex::new(r#s).ok()),
+                    split_expr.next().and_then(|s| Regex::new(r#s).ok()),
+                ) {
+                    (Some(y), targets, Some(re)) => (y, targets, re),
+                    _ => {
+                        println!("error: specify literal, targets and facets");
+                        return Ok(());
+                    }
+                };
+                let selection = match Selection::parse(split_expr) {
+                    Some(selection) => selection,
+                    _ => {
+                        println!(
+                            "error: specify options among {{sort inc|exc, top k, inc t, exc t}}"
+                        );
+                        return Ok(());
+                    }
+                };
+
+                let s = projection(nav, atoms, targets);
+                nav.add_rule(s.clone())?;
+                let rows = session
+                    .counter(nav)
+                    .significance_projecting(route, y, facets, &re);
                 nav.remove_rule(s.clone())?;
+
+                print_significance(nav, route, y, &selection.apply(rows?))?;
             }
             Some(ENUMERATE_PROJECTED_SOLUTIONS) => {
-                let n = nav.enumerate_projected_solutions(
-                    split_expr
-                        .next()
-                        .and_then(|n| n.parse::<usize>().ok())
-                        .take(),
-                    route.iter().map(|s| s.as_ref()).chain(split_expr),
-                    facets.clone(),
-                )?;
-                println!("found {:?}", n);
+                let n = split_expr.next().and_then(|n| n.parse::<usize>().ok());
+                let active = route
+                    .iter()
+                    .cloned()
+                    .chain(split_expr.map(|l| l.to_owned()))
+                    .collect::<Vec<_>>();
+                // NOTE: solvers of the same program and arguments find answer sets in the same
+                // order, hence kept answer sets are numbered as printed
+                let (program, args) = (nav.program(), session.solver_args());
+                let found = Navigator::new(program.clone(), args.clone())?
+                    .enumerate_projected_solutions(n, active.iter(), facets.clone())?;
+                println!("found {:?}", found);
+
+                let shown = facets.iter().collect::<std::collections::HashSet<_>>();
+                let kept = Some(found.min(solutions::CAPACITY));
+                let (_, answer_sets) =
+                    solutions::enumerate(&program, &args, &active, kept, |_, _| ())?;
+                session.solutions = answer_sets
+                    .into_iter()
+                    .map(|answer_set| {
+                        answer_set
+                            .into_iter()
+                            .filter(|a| shown.contains(a))
+                            .collect::<Vec<_>>()
+                    })
+                    .collect();
             }
+            Some(cmd) if cmd.starts_with(ACTIVATE_SOLUTION) => {
+                let id = &cmd[ACTIVATE_SOLUTION.len()..];
+                match session.answer_set(id) {
+                    Some(answer_set) => {
+                        route.extend(solutions::literals(answer_set, facets));
+                        *facets = session
+                            .history
+                            .facets(nav, route)
+                            .ok_or(NavigatorError::None)?;
+                    }
+                    _ => println!("error: unknown answer set {id}"),
+                }
+            }
+            Some(EXPORT_SOLUTION) => match (
+                split_expr.next().and_then(|id| session.answer_set(id)),
+                split_expr.next(),
+            ) {
+                (Some(answer_set), Some(filename)) => {
+                    let facts = answer_set
+                        .iter()
+                        .map(|a| format!("{a}.\n"))
+                        .collect::<String>();
+                    if let Err(e) = std::fs::write(filename, facts) {
+                        println!("error: {e}")
+                    }
+                }
+                _ => println!("error: specify known answer set and filename"),
+            },
+            Some(DISTINGUISH) => match split_expr.next().and_then(|id| session.addressed(id)) {
+                Some((answer_sets, i)) => {
+                    let lits = solutions::distinguishing(answer_sets, i, facets);
+                    for (l, n) in &lits {
+                        println!("{l} excludes {n}");
+                    }
+                    let excluded = lits.iter().map(|(_, n)| n).sum::<usize>();
+                    if excluded + 1 < answer_sets.len() {
+                        println!(
+                            "info: {} answer sets agree on all facets",
+                            answer_sets.len() - 1 - excluded
+                        );
+                    }
+                }
+                _ => println!("error: unknown answer set"),
+            },
             Some(cmd) => {
                 if cmd.starts_with("//") {
                     return Ok(());
@@ -1074,3 +1947,30 @@ impl Evaluate<Option<usize>> for Mode<Option<usize>> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{strings, TINY};
+    use crate::pool::Counter;
+
+    #[test]
+    fn tiny_projected_counts() -> Result<()> {
+        let mut nav = Navigator::new(TINY, vec!["0".to_string()])?;
+        let atoms = strings(&["a", "b", "c", "d", "e"]);
+        let routes = [vec![], strings(&["b"])];
+
+        let counts = Counter::new(&mut nav, None).count_all(&Weight::FacetCounting, &routes);
+        assert_eq!(counts, [Some(8), Some(4)]);
+
+        let rule = projection(&nav, &atoms, Regex::new("^[ab]$").ok());
+        nav.add_rule(&rule)?;
+        let mut counter = Counter::new(&mut nav, None).projecting();
+        assert_eq!(
+            counter.count_all(&Weight::FacetCounting, &routes),
+            [Some(4), Some(0)]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Installs a Ctrl-C handler that requests cancellation of the running command.
+///
+/// A second Ctrl-C, while the first one is still pending, terminates fasb.
+pub fn install() {
+    if let Err(err) = ctrlc::set_handler(|| {
+        if INTERRUPTED.swap(true, Ordering::SeqCst) {
+            std::process::exit(130)
+        }
+    }) {
+        eprintln!("error: could not install Ctrl-C handler: {err}");
+    }
+}
+
+/// Returns whether cancellation was requested since last [reset].
+pub fn requested() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Clears pending cancellation requests.
+pub fn reset() {
+    INTERRUPTED.store(false, Ordering::SeqCst)
+}
@@ -1,11 +1,8 @@
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
 
-use indicatif::ProgressBar;
+use crate::modes::Budget;
 use savan::nav::Navigator;
 
-use std::thread;
-use std::time::Duration;
-
 pub fn copy_program(lp: String) -> String {
     let mut xs = vec![];
     for x in lp.chars() {
@@ -54,22 +51,6 @@ pub fn copy_atom(atom: &str) -> String {
     xs.iter().collect::<String>()
 }
 
-pub fn copied_atom(atom: &str) -> String {
-    let mut xs = vec![];
-    for (i, x) in atom.chars().enumerate() {
-        match x {
-            '_' => {
-                if (i + 1) % 2 != 0 {
-                    continue;
-                }
-            }
-            _ => xs.push(x),
-        }
-    }
-
-    xs.iter().collect::<String>()
-}
-
 pub fn is_facet_r(nav: &mut Navigator, atom: String) -> bool {
     let c = copy_atom(&atom);
     match nav.enumerate_solutions_quietly(Some(1), [atom.clone(), format!("~{c}")].iter()) {
@@ -88,154 +69,279 @@ pub fn is_facet(nav: &mut Navigator, atom: String) -> bool {
     }
 }
 
-#[allow(unused)]
-pub fn rec_red(
+/// Answer sets that together cover target atoms.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Representatives {
+    pub answer_sets: Vec<Vec<String>>,
+    /// Target atoms each representative covers first.
+    pub covers: Vec<Vec<String>>,
+    /// Target atoms true in some representative, in order of coverage.
+    pub covered: Vec<String>,
+}
+
+/// Extends **reps** by answer sets under **route** until each of **target_atoms** is covered or
+/// false in every answer set, searching one answer set per target atom not covered so far.
+pub fn rec_soe(
     nav: &mut Navigator,
+    route: &[String],
     target_atoms: Vec<String>,
-    mut fs: Vec<String>,
-    mut seen: Vec<String>,
-    n: u64,
-    mut k: u64,
-    pb: ProgressBar,
-) -> Vec<String> {
-    //dbg!("new", &target_atoms, &fs, &seen, n, k);
-    if k >= n {
-        return fs;
-    }
-    if let Some(alpha) = target_atoms.iter().filter(|a| !seen.contains(a)).next() {
-        //dbg!(k, &rest);
-        if k >= n {
-            return fs;
+    mut reps: Representatives,
+    budget: &Budget,
+) -> Representatives {
+    let mut uncovered = VecDeque::from(target_atoms);
+    while let Some(alpha) = uncovered.pop_front() {
+        if budget.exhausted() {
+            break;
         }
-        let mut rest = vec![];
-        seen.push(alpha.to_string());
-        let delta = vec![alpha.clone(), format!("~{}", copy_atom(&alpha))];
-        match nav.one_or_none(delta.iter()) {
-            Some(answer_set) => {
-                fs.push(alpha.to_string());
-                //println!("proof {alpha} {:?} {k} check", &target_atoms);
-                k += 1;
-                pb.set_position(k);
-                thread::sleep(Duration::from_millis(12));
-                if k >= n {
-                    return fs;
+
+        let answer_set = nav
+            .one_or_none(route.iter().chain([&alpha]))
+            .map(|xs| xs.into_iter().collect::<HashSet<_>>())
+            // NOTE: unknown atoms are dropped from assumptions
+            .filter(|xs| xs.contains(&alpha));
+        budget.tick();
+
+        if let Some(answer_set) = answer_set {
+            let mut covers = vec![alpha];
+            uncovered.retain(|a| match answer_set.contains(a) {
+                true => {
+                    covers.push(a.clone());
+                    budget.tick();
+                    false
                 }
-                //dbg!(&answer_set);
-                for beta in answer_set.iter() {
-                    if beta == alpha || seen.contains(beta) || seen.contains(&copied_atom(beta)) {
-                        continue;
+                _ => true,
+            });
+
+            reps.covered.extend(covers.clone());
+            reps.covers.push(covers);
+            let mut answer_set = answer_set.into_iter().collect::<Vec<_>>();
+            answer_set.sort();
+            reps.answer_sets.push(answer_set);
+        }
+    }
+
+    reps
+}
+
+/// Options that control size and diversity of representatives.
+#[derive(Debug, Clone, Default)]
+pub struct Diversity {
+    /// Maximum number of representatives, unbounded if `None`.
+    pub max: Option<usize>,
+    /// Least Hamming distance over target atoms between any two representatives.
+    pub distance: usize,
+    /// Picks the representative that covers the most target atoms not covered so far.
+    pub greedy: bool,
+}
+impl Diversity {
+    /// Parses options=[max k, dist d, greedy] from **args**.
+    pub fn parse<'a>(mut args: impl Iterator<Item = &'a str>) -> Option<Self> {
+        let mut diversity = Self::default();
+        while let Some(option) = args.next() {
+            match option {
+                "max" => diversity.max = Some(args.next()?.parse::<usize>().ok()?),
+                "dist" => diversity.distance = args.next()?.parse::<usize>().ok()?,
+                "greedy" => diversity.greedy = true,
+                _ => return None,
+            }
+        }
+        Some(diversity)
+    }
+}
+
+/// Number of **targets** true in exactly one of **xs** and **ys**.
+fn hamming(xs: &HashSet<String>, ys: &HashSet<String>, targets: &[String]) -> usize {
+    targets
+        .iter()
+        .filter(|a| xs.contains(*a) != ys.contains(*a))
+        .count()
+}
+
+/// Answer set under **route** with **alpha** at least **distance** apart from each of **chosen**
+/// over **targets**.
+fn candidate(
+    nav: &mut Navigator,
+    route: &[String],
+    alpha: &String,
+    targets: &[String],
+    chosen: &[HashSet<String>],
+    distance: usize,
+) -> Option<HashSet<String>> {
+    let far = |xs: &HashSet<String>| {
+        xs.contains(alpha) && chosen.iter().all(|ys| hamming(xs, ys, targets) >= distance)
+    };
+
+    let first = nav
+        .one_or_none(route.iter().chain([alpha]))?
+        .into_iter()
+        .collect::<HashSet<_>>();
+    if far(&first) {
+        return Some(first);
+    }
+    if !first.contains(alpha) {
+        return None;
+    }
+
+    // NOTE: retries away from the target atoms of the closest representative
+    let closest = chosen
+        .iter()
+        .min_by_key(|ys| hamming(&first, ys, targets))?;
+    let away = targets
+        .iter()
+        .filter(|a| *a != alpha && closest.contains(*a))
+        .map(|a| format!("~{a}"));
+    let route = route
+        .iter()
+        .cloned()
+        .chain([alpha.clone()])
+        .chain(away)
+        .collect::<Vec<_>>();
+    nav.one_or_none(route.iter())
+        .map(|xs| xs.into_iter().collect::<HashSet<_>>())
+        .filter(far)
+}
+
+/// Representatives under **route** that cover **target_atoms** subject to **diversity**.
+///
+/// Greedy selection searches a candidate per target atom not covered so far in each round, and
+/// therefore needs more solver calls than taking candidates in order of **target_atoms**.
+pub fn diverse_soe(
+    nav: &mut Navigator,
+    route: &[String],
+    target_atoms: &[String],
+    diversity: &Diversity,
+    budget: &Budget,
+) -> Representatives {
+    let mut reps = Representatives::default();
+    let mut chosen: Vec<HashSet<String>> = vec![];
+    let mut uncovered = target_atoms.to_vec();
+
+    while !uncovered.is_empty()
+        && diversity.max.is_none_or(|k| reps.answer_sets.len() < k)
+        && !budget.exhausted()
+    {
+        let d = diversity.distance;
+        let picked = match diversity.greedy {
+            true => {
+                let mut best: Option<(usize, HashSet<String>)> = None;
+                for alpha in &uncovered {
+                    if budget.exhausted() {
+                        break;
                     }
-                    match beta.starts_with('_') {
-                        true => match !answer_set.contains(&copied_atom(beta)) {
-                            true => {
-                                //println!("proof {beta} {:?} {k} nocopy", &target_atoms);
-                                //dbg!(beta, copied_atom(beta));
-                                seen.push(copied_atom(beta));
-                                fs.push(copied_atom(beta));
-                                k += 1;
-                                pb.set_position(k);
-                                thread::sleep(Duration::from_millis(12));
-                                if k >= n {
-                                    return fs;
-                                }
-                            }
-                            _ => {
-                                if !seen.contains(&copied_atom(beta)) {
-                                    rest.push(copied_atom(beta));
-                                }
-                            }
-                        },
-                        _ => match !answer_set.contains(&copy_atom(beta)) {
-                            true => {
-                                //println!("proof {beta} {:?} {k} noactual", &target_atoms);
-                                seen.push(beta.to_string());
-                                fs.push(beta.to_string());
-                                k += 1;
-                                pb.set_position(k);
-                                thread::sleep(Duration::from_millis(12));
-                                if k >= n {
-                                    return fs;
-                                }
-                            }
-                            _ => {
-                                if !seen.contains(&copied_atom(beta)) {
-                                    rest.push(copied_atom(beta));
-                                }
-                            }
-                        },
+                    if let Some(xs) = candidate(nav, route, alpha, target_atoms, &chosen, d) {
+                        let gain = uncovered.iter().filter(|a| xs.contains(*a)).count();
+                        if best.as_ref().is_none_or(|(g, _)| gain > *g) {
+                            best = Some((gain, xs));
+                        }
                     }
                 }
-
-                return rec_red(
-                    nav,
-                    rest.iter()
-                        .cloned()
-                        .chain(target_atoms.iter().cloned().filter(|a| !seen.contains(a)))
-                        .collect::<HashSet<_>>()
-                        .iter()
-                        .cloned()
-                        .collect::<Vec<_>>(),
-                    fs,
-                    seen.clone(),
-                    n,
-                    k,
-                    pb,
-                );
+                best.map(|(_, xs)| xs)
             }
             _ => {
-                //println!("disproof {alpha} {:?} {k}", &target_atoms);
-                k += 1;
-                pb.set_position(k);
-                thread::sleep(Duration::from_millis(12));
-                return rec_red(
-                    nav,
-                    target_atoms.iter().cloned().skip(1).collect::<Vec<_>>(),
-                    fs,
-                    seen.clone(),
-                    n,
-                    k,
-                    pb,
-                );
+                let mut picked = None;
+                while let Some(alpha) = uncovered.first().cloned() {
+                    match candidate(nav, route, &alpha, target_atoms, &chosen, d) {
+                        Some(xs) => {
+                            picked = Some(xs);
+                            break;
+                        }
+                        _ => {
+                            uncovered.remove(0);
+                            budget.tick();
+                        }
+                    }
+                }
+                picked
             }
-        }
-    } else {
-        return fs;
+        };
+        let xs = match picked {
+            Some(xs) => xs,
+            _ => break,
+        };
+
+        let (covers, rest): (Vec<_>, Vec<_>) = uncovered.into_iter().partition(|a| xs.contains(a));
+        covers.iter().for_each(|_| budget.tick());
+        uncovered = rest;
+
+        chosen.push(
+            target_atoms
+                .iter()
+                .filter(|a| xs.contains(*a))
+                .cloned()
+                .collect(),
+        );
+        reps.covered.extend(covers.clone());
+        reps.covers.push(covers);
+        let mut answer_set = xs.into_iter().collect::<Vec<_>>();
+        answer_set.sort();
+        reps.answer_sets.push(answer_set);
     }
 
-    //fs.iter()
-    //    .cloned()
-    //    .collect::<HashSet<_>>()
-    //    .iter()
-    //    .cloned()
-    //    .collect::<Vec<_>>()
+    reps
 }
 
-#[allow(unused)]
-pub fn rec_soe(
-    nav: &mut Navigator,
-    target_atoms: Vec<String>,
-    mut fs: Vec<String>,
-    n: u64,
-    mut k: u64,
-    pb: ProgressBar,
-) -> Vec<String> {
-    //if let Some((mut to_observe, fs_, falsified_atom)) = nav.sieve_quiet(&target_atoms) {
-    //    for f in fs_.iter() {
-    //        fs.push(f.to_string())
-    //    }
-    //    to_observe
-    //        .iter()
-    //        .position(|x| *x == falsified_atom)
-    //        .map(|i| to_observe.remove(i))
-    //        .unwrap();
-    //    println!("some {:?} {:?} {:?}", to_observe.len(), fs_.len(), fs.len());
-    //    rec_soe(nav, to_observe, fs.clone(), n, k, pb)
-    //} else {
-    //    println!("done {:?}", target_atoms.clone());
-    //    for f in target_atoms {
-    //        fs.push(f)
-    //    }
-    //    return fs;
-    //}
-    unimplemented!()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::TINY;
+    use savan::nav::errors::Result;
+
+    #[test]
+    fn tiny_rec_soe() -> Result<()> {
+        let mut nav = Navigator::new(TINY, vec!["0".to_string()])?;
+        let targets = ["a", "b", "c", "d", "x"].map(|a| a.to_owned()).to_vec();
+
+        let reps = rec_soe(
+            &mut nav,
+            &[],
+            targets,
+            Representatives::default(),
+            &Budget::default(),
+        );
+        assert_eq!(reps.answer_sets.len(), 3);
+        assert_eq!(reps.answer_sets[0], ["a", "e"]);
+        let mut covered = reps.covered.clone();
+        covered.sort();
+        assert_eq!(covered, ["a", "b", "c", "d"]);
+
+        let reps = rec_soe(
+            &mut nav,
+            &["b".to_owned()],
+            ["a", "c"].map(|a| a.to_owned()).to_vec(),
+            Representatives::default(),
+            &Budget::default(),
+        );
+        assert_eq!(reps.answer_sets, [["b", "c", "e"]]);
+        assert_eq!(reps.covered, ["c"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn tiny_diverse_soe() -> Result<()> {
+        let mut nav = Navigator::new(TINY, vec!["0".to_string()])?;
+        let targets = ["a", "b", "c", "d"].map(|a| a.to_owned());
+        let budget = Budget::default();
+
+        let diversity = Diversity::parse("max 2".split_whitespace()).unwrap();
+        let reps = diverse_soe(&mut nav, &[], &targets, &diversity, &budget);
+        assert_eq!(reps.answer_sets.len(), 2);
+        assert_eq!(reps.covers[0], ["a"]);
+
+        let diversity = Diversity::parse("greedy".split_whitespace()).unwrap();
+        let reps = diverse_soe(&mut nav, &[], &targets, &diversity, &budget);
+        assert_eq!(reps.answer_sets.len(), 3);
+        assert_eq!(reps.covers[0].len(), 2);
+        assert!(reps.answer_sets[0].contains(&"b".to_owned()));
+
+        // NOTE: answer sets with b differ in c and d only
+        let diversity = Diversity::parse("dist 3".split_whitespace()).unwrap();
+        let reps = diverse_soe(&mut nav, &[], &targets, &diversity, &budget);
+        assert_eq!(reps.answer_sets.len(), 2);
+        assert_eq!(reps.covered.len(), 3);
+
+        assert!(Diversity::parse("max".split_whitespace()).is_none());
+
+        Ok(())
+    }
 }
@@ -3,21 +3,36 @@ use regex::Regex;
 use rustyline::error::ReadlineError;
 #[cfg(not(feature = "interpreter"))]
 use rustyline::DefaultEditor;
-use savan::lex;
 use savan::nav::errors::{NavigatorError, Result};
 use savan::nav::{facets::Facets, Navigator};
 use std::fs::read_to_string;
 use std::path::Path;
+mod cache;
 mod config;
+#[cfg(test)]
+mod fixtures;
+mod history;
+mod implications;
 mod interpreter;
+mod interrupt;
 mod is_facet;
 mod modes;
+mod optimize;
+mod pool;
+mod sample;
+mod session;
 mod significance;
+mod solutions;
+mod ties;
+mod weights;
 mod wfc;
 #[cfg(feature = "interpreter")]
 use crate::config::PROMPT;
 use crate::interpreter::Evaluate;
 use crate::modes::Mode;
+use crate::pool::Pool;
+use crate::session::Session;
+use crate::weights::Registry;
 
 #[cfg(not(feature = "interpreter"))]
 fn main() -> Result<()> {
@@ -52,6 +67,17 @@ fn main() -> Result<()> {
         };
         args.remove(i);
     }
+    let mut threads = 1;
+    if let Some(i) = args.iter().position(|x| *x == "--threads") {
+        threads = match args.get(i + 1).and_then(|n| n.parse::<usize>().ok()) {
+            Some(n) if n > 0 => n,
+            _ => {
+                println!("error: expected number of threads");
+                std::process::exit(-1)
+            }
+        };
+        args.drain(i..i + 2);
+    }
     let lp = read_to_string(Path::new(&arg)).map_err(|_| NavigatorError::None)?;
 
     let re = Regex::new(r#config::FILTER_KEYWORD).unwrap();
@@ -83,14 +109,26 @@ fn main() -> Result<()> {
     let mut route = Vec::new();
     let mut cnf = Vec::new();
 
+    let pool = match threads > 1 {
+        true => Pool::new(threads, &nav.program(), args.clone())
+            .map_err(|e| eprintln!("error: could not start solvers, counting on one thread: {e:?}"))
+            .ok(),
+        _ => None,
+    };
+
+    let mut session = Session {
+        pool,
+        args: args.clone(),
+        weights: Registry::new(&args),
+        ..Default::default()
+    };
+
     let mut facets = if facets_at_startup {
         match learned_that_at_startup {
-            false => nav
-                .facet_inducing_atoms(route.iter())
-                .ok_or(NavigatorError::None)?
-                .into_iter()
-                .map(lex::repr)
-                .collect::<Vec<_>>(),
+            false => session
+                .history
+                .facets(&mut nav, &route)
+                .ok_or(NavigatorError::None)?,
             _ => nav
                 .learned_that(&atoms, &route, None)
                 .ok_or(NavigatorError::None)?,
@@ -99,6 +137,8 @@ fn main() -> Result<()> {
         vec![]
     };
 
+    interrupt::install();
+
     let mut rl = DefaultEditor::new().map_err(|_| NavigatorError::None)?;
 
     for a in nav.atoms() {
@@ -114,6 +154,7 @@ fn main() -> Result<()> {
                     eprintln!("ReadlineError: {:?}", err);
                 }
 
+                interrupt::reset();
                 mode.command(
                     line,
                     &mut nav,
@@ -121,6 +162,7 @@ fn main() -> Result<()> {
                     &mut facets,
                     &mut route,
                     &mut cnf,
+                    &mut session,
                 )?;
             }
             Err(ReadlineError::Interrupted) => {}
@@ -181,6 +223,17 @@ fn main() -> Result<()> {
         };
         args.remove(i);
     }
+    let mut threads = 1;
+    if let Some(i) = args.iter().position(|x| *x == "--threads") {
+        threads = match args.get(i + 1).and_then(|n| n.parse::<usize>().ok()) {
+            Some(n) if n > 0 => n,
+            _ => {
+                println!("error: expected number of threads");
+                std::process::exit(-1)
+            }
+        };
+        args.drain(i..i + 2);
+    }
 
     let clp = is_facet::copy_program(lp.clone());
     let mut _nav = Navigator::new(format!("{lp}\n{clp}"), args.clone())?;
@@ -199,7 +252,7 @@ fn main() -> Result<()> {
         _ => todo!(),
     };
 
-    let mut nav = Navigator::new(lp, args)?;
+    let mut nav = Navigator::new(lp, args.clone())?;
     let mut mode = Mode::GoalOriented(None::<usize>);
 
     let mut atoms = nav
@@ -208,14 +261,27 @@ fn main() -> Result<()> {
         .collect::<Vec<String>>();
     let mut route = Vec::new();
     let mut ctx = Vec::new();
+
+    let pool = match threads > 1 {
+        true => Pool::new(threads, &nav.program(), args.clone())
+            .map_err(|e| eprintln!("error: could not start solvers, counting on one thread: {e:?}"))
+            .ok(),
+        _ => None,
+    };
+
+    let mut session = Session {
+        pool,
+        args: args.clone(),
+        weights: Registry::new(&args),
+        ..Default::default()
+    };
+
     let mut facets = if facets_at_startup {
         match learned_that_at_startup {
-            false => nav
-                .facet_inducing_atoms(route.iter())
-                .ok_or(NavigatorError::None)?
-                .iter()
-                .map(|f| lex::repr(*f))
-                .collect::<Vec<_>>(),
+            false => session
+                .history
+                .facets(&mut nav, &route)
+                .ok_or(NavigatorError::None)?,
             _ => nav
                 .learned_that(&atoms, &route, None)
                 .ok_or(NavigatorError::None)?,
@@ -224,8 +290,11 @@ fn main() -> Result<()> {
         vec![]
     };
 
+    interrupt::install();
+
     for line in script.lines() {
         println!("{PROMPT}{line}");
+        interrupt::reset();
         mode.command(
             line.to_owned(),
             &mut nav,
@@ -233,6 +302,7 @@ fn main() -> Result<()> {
             &mut facets,
             &mut route,
             &mut ctx,
+            &mut session,
         )?
     }
 
@@ -1,37 +1,144 @@
-use savan::nav::{
-    weights::{count, Weight},
-    Navigator,
-};
+use crate::pool::Counter;
+use crate::ties::{mix, TieBreak};
+use indicatif::{ProgressBar, ProgressState, ProgressStyle};
+use savan::nav::weights::Weight;
+use std::cell::Cell;
+use std::cmp::Ordering;
 use std::fmt;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
 
 pub fn propose_next_step<T>(
     mode: &mut impl Step<T>,
-    nav: &mut Navigator,
-    active: &mut Vec<String>,
+    counter: &mut Counter,
+    active: &[String],
     facets: &[String],
+    ties: &TieBreak,
+    budget: &Budget,
 ) -> Option<(String, T)>
 where
     T: Clone + Eq + PartialEq,
 {
-    mode.propose_facet(nav, active, facets)
+    mode.propose_facet_within(counter, active, facets, ties, budget)
 }
 
 pub fn perform_next_step<T>(
     mode: &mut impl Step<T>,
-    nav: &mut Navigator,
+    counter: &mut Counter,
     active: &mut Vec<String>,
     facets: &[String],
+    ties: &TieBreak,
+    budget: &Budget,
 ) -> Option<(String, T)>
 where
     T: Clone + Eq + PartialEq,
 {
-    mode.propose_facet(nav, active, facets)
+    mode.propose_facet_within(counter, active, facets, ties, budget)
         .and_then(|(facet, count)| {
             active.push(facet.clone());
             Some((facet, count))
         })
 }
 
+/// Activates the **i**-th best candidate among **facets**, counting from 1.
+pub fn perform_nth_step<T>(
+    mode: &mut impl Step<T>,
+    counter: &mut Counter,
+    active: &mut Vec<String>,
+    facets: &[String],
+    i: usize,
+    ties: &TieBreak,
+    budget: &Budget,
+) -> Option<(String, T)>
+where
+    T: Clone + Eq + PartialEq,
+{
+    mode.rank_facets_within(counter, active, facets, ties, budget)
+        .into_iter()
+        .nth(i.checked_sub(1)?)
+        .map(|(facet, count)| {
+            active.push(facet.clone());
+            (facet, count)
+        })
+}
+
+/// Bounds the evaluation of a step in time and by Ctrl-C, while reporting progress.
+///
+/// The budget is checked between counts, a single count runs to completion.
+#[derive(Default)]
+pub struct Budget {
+    deadline: Option<Instant>,
+    progress: Option<ProgressBar>,
+    stopped: Cell<bool>,
+}
+impl Budget {
+    /// Budget of **timeout** for scoring **n** facets, displayed as progress bar.
+    pub fn new(timeout: Option<Duration>, n: usize) -> Self {
+        let pb = ProgressBar::new(n as u64);
+        let style = "{spinner:.green} [{elapsed_precise}] [{wide_bar}] {pos}/{len} ({eta})";
+        pb.set_style(ProgressStyle::with_template(style).unwrap().with_key(
+            "eta",
+            |state: &ProgressState, w: &mut dyn Write| {
+                write!(w, "{:.1}s", state.eta().as_secs_f64()).unwrap()
+            },
+        ));
+
+        Self {
+            deadline: timeout.map(|t| Instant::now() + t),
+            progress: Some(pb),
+            stopped: Cell::new(false),
+        }
+    }
+
+    /// Budget of **timeout** without progress bar.
+    pub fn quiet(timeout: Option<Duration>) -> Self {
+        Self {
+            deadline: timeout.map(|t| Instant::now() + t),
+            progress: None,
+            stopped: Cell::new(false),
+        }
+    }
+
+    /// Checks whether time is up or cancellation was requested.
+    pub fn exhausted(&self) -> bool {
+        let exhausted =
+            crate::interrupt::requested() || self.deadline.is_some_and(|d| Instant::now() >= d);
+        if exhausted {
+            self.stopped.set(true);
+        }
+        exhausted
+    }
+
+    /// Returns whether some evaluation was cut short by this budget.
+    pub fn stopped_early(&self) -> bool {
+        self.stopped.get()
+    }
+
+    pub fn tick(&self) {
+        if let Some(pb) = &self.progress {
+            pb.inc(1)
+        }
+    }
+
+    /// Prints **line** to stdout without garbling the progress bar.
+    pub fn println(&self, line: impl AsRef<str>) {
+        match &self.progress {
+            Some(pb) => pb.suspend(|| println!("{}", line.as_ref())),
+            _ => println!("{}", line.as_ref()),
+        }
+    }
+
+    pub fn finish(&self) {
+        if let Some(pb) = &self.progress {
+            pb.finish_and_clear()
+        }
+    }
+}
+
+/// Navigation mode.
+///
+/// Counting modes hold the count after their last step, until the route or context changes.
+/// Steps derive counts from the current route instead.
 #[derive(Clone)]
 pub enum Mode<T> {
     GoalOriented(T),
@@ -39,6 +146,13 @@ pub enum Mode<T> {
     MaxWeightedFacetCounting(T),
     MinWeightedAnswerSetCounting(T),
     MaxWeightedAnswerSetCounting(T),
+    /// Activates pseudo-random literals, determined by a seed, drawn uniformly among the
+    /// literals of the proposed facets; answer sets reached are not drawn uniformly.
+    Random(T),
+    /// Explores by the active weight function.
+    MinWeighted(T),
+    /// Strictly goal oriented by the active weight function.
+    MaxWeighted(T),
 }
 impl Mode<Option<usize>> {
     pub fn update(&mut self, with: Option<usize>) {
@@ -52,6 +166,31 @@ impl Mode<Option<usize>> {
             Self::MinWeightedAnswerSetCounting(_) => {
                 *self = Self::MinWeightedAnswerSetCounting(with)
             }
+            // NOTE: keeps seed
+            Self::Random(_) => (),
+            Self::MinWeighted(_) => *self = Self::MinWeighted(with),
+            Self::MaxWeighted(_) => *self = Self::MaxWeighted(with),
+        }
+    }
+
+    /// Count under **active** that reduction ratios of steps refer to.
+    pub fn reference_count(
+        &self,
+        counter: &mut Counter,
+        active: &[String],
+        facets: &[String],
+    ) -> Option<usize> {
+        match self {
+            Self::MaxWeightedFacetCounting(_) | Self::MinWeightedFacetCounting(_) => counter
+                .count(&Weight::FacetCounting, active)
+                .or(Some(2 * facets.len())),
+            Self::MaxWeightedAnswerSetCounting(_) | Self::MinWeightedAnswerSetCounting(_) => {
+                counter.count(&Weight::AnswerSetCounting, active)
+            }
+            Self::GoalOriented(_)
+            | Self::Random(_)
+            | Self::MinWeighted(_)
+            | Self::MaxWeighted(_) => Some(usize::default()),
         }
     }
 }
@@ -72,6 +211,11 @@ impl fmt::Display for Mode<Option<usize>> {
             Self::MinWeightedAnswerSetCounting(_) => {
                 write!(f, "explore mode counting answer sets (min#as)")
             }
+            Self::Random(seed) => write!(f, "random mode (random {})", seed.unwrap_or_default()),
+            Self::MinWeighted(_) => write!(f, "explore mode by active weight (min#w)"),
+            Self::MaxWeighted(_) => {
+                write!(f, "strictly goal oriented mode by active weight (max#w)")
+            }
         }
     }
 }
@@ -80,209 +224,452 @@ pub trait Step<T>
 where
     T: Clone + Eq + PartialEq,
 {
-    fn propose_facet(
+    /// Proposes a facet, returning the best candidate scored before **budget** ran out.
+    ///
+    /// Candidates of equal count are ordered by **ties**.
+    fn propose_facet_within(
         &self,
-        nav: &mut Navigator,
-        active: &mut Vec<String>,
+        counter: &mut Counter,
+        active: &[String],
         among: &[String],
+        ties: &TieBreak,
+        budget: &Budget,
     ) -> Option<(String, T)>;
+    /// Ranks candidates from best to worst, among those scored before **budget** ran out.
+    fn rank_facets_within(
+        &self,
+        counter: &mut Counter,
+        active: &[String],
+        among: &[String],
+        ties: &TieBreak,
+        budget: &Budget,
+    ) -> Vec<(String, T)>;
+}
+
+/// Scores facets in **among** chunk-wise under **active** by **weigh**, and passes the scores
+/// of the literals that **literals** yields for a facet to **visit**, until **visit** returns
+/// `false` or **budget** is exhausted.
+fn score<'a, V>(
+    counter: &mut Counter,
+    mut weigh: impl FnMut(&mut Counter, &[Vec<String>]) -> Vec<Option<V>>,
+    active: &[String],
+    among: &'a [String],
+    budget: &Budget,
+    literals: impl Fn(&str) -> Vec<String>,
+    mut visit: impl FnMut(&'a str, Vec<(String, Option<V>)>) -> bool,
+) {
+    for chunk in among.chunks(counter.width()) {
+        if budget.exhausted() {
+            return;
+        }
+        let lits = chunk.iter().map(|f| literals(f)).collect::<Vec<_>>();
+        let routes = lits
+            .iter()
+            .flatten()
+            .map(|l| active.iter().chain([l]).cloned().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let mut counts = weigh(counter, &routes).into_iter();
+
+        for (facet, ls) in chunk.iter().zip(lits) {
+            let scored = ls
+                .into_iter()
+                .map(|l| (l, counts.next().flatten()))
+                .collect::<Vec<_>>();
+            budget.tick();
+            if !visit(facet, scored) {
+                return;
+            }
+        }
+    }
+}
+
+/// Ranks literals of facets in **among** by the weight of **counter** under **active**, from
+/// best to worst in **mode**, among those weighed before **budget** ran out.
+pub fn rank_weighted(
+    mode: &Mode<Option<usize>>,
+    counter: &mut Counter,
+    active: &[String],
+    among: &[String],
+    ties: &TieBreak,
+    budget: &Budget,
+) -> Vec<(String, f64)> {
+    let mut ranked = vec![];
+    score(
+        counter,
+        |c, routes| c.weigh_all(routes),
+        active,
+        among,
+        budget,
+        |facet| vec![facet.to_owned(), format!("~{facet}")],
+        |_, scored| {
+            ranked.extend(scored.into_iter().filter_map(|(l, w)| w.map(|w| (l, w))));
+            true
+        },
+    );
+
+    let ord = |x: &f64, y: &f64| x.partial_cmp(y).unwrap_or(Ordering::Equal);
+    match mode {
+        Mode::MinWeighted(_) => {
+            ranked.sort_by(|(x, v), (y, w)| ord(w, v).then_with(|| ties.cmp(x, y)))
+        }
+        _ => ranked.sort_by(|(x, v), (y, w)| ord(v, w).then_with(|| ties.cmp(x, y))),
+    }
+
+    ranked
+}
+
+/// Answer set count under the negation of a facet, given answer set counts **total** without
+/// and **counted** under the facet.
+fn complement(total: Option<usize>, counted: Option<usize>) -> Option<usize> {
+    // NOTE: every answer set either includes or excludes the facet
+    total.zip(counted).and_then(|(x, y)| x.checked_sub(y))
 }
+
+/// Scores answer set counts under **active** extended by both **literals** of each facet in
+/// **among**, where the latter literal is complemented from the count **total** under **active**
+/// if possible.
+fn score_answer_sets<'a>(
+    counter: &mut Counter,
+    total: Option<usize>,
+    active: &[String],
+    among: &'a [String],
+    budget: &Budget,
+    literals: impl Fn(&str) -> [String; 2],
+    mut visit: impl FnMut(&'a str, Vec<(String, Option<usize>)>) -> bool,
+) {
+    // NOTE: projected solutions may both include and exclude a facet
+    let complementable = total.filter(|_| !counter.projecting);
+    score(
+        counter,
+        |c, routes| c.count_all(&Weight::AnswerSetCounting, routes),
+        active,
+        among,
+        budget,
+        |facet| match (complementable, literals(facet)) {
+            (Some(_), [l, _]) => vec![l],
+            (_, ls) => ls.to_vec(),
+        },
+        |facet, mut scored| {
+            if complementable.is_some() {
+                let [_, l] = literals(facet);
+                scored.push((l, complement(complementable, scored[0].1)));
+            }
+            visit(facet, scored)
+        },
+    )
+}
+
 impl Step<Option<usize>> for Mode<Option<usize>> {
-    fn propose_facet(
+    fn propose_facet_within(
         &self,
-        nav: &mut Navigator,
-        active: &mut Vec<String>,
+        counter: &mut Counter,
+        active: &[String],
         among: &[String],
+        ties: &TieBreak,
+        budget: &Budget,
     ) -> Option<(String, Option<usize>)> {
         if among.is_empty() {
             return None;
         }
+        if !matches!(ties, TieBreak::Solver) {
+            // NOTE: early returns on bounds depend on the order of facets
+            return self
+                .rank_facets_within(counter, active, among, ties, budget)
+                .into_iter()
+                .next();
+        }
 
         match self {
             Self::GoalOriented(_) => among.iter().next().map(|f| (f.to_string(), None)),
-            Self::MaxWeightedFacetCounting(prev_count) => {
-                let mut counted;
+            Self::Random(_) | Self::MinWeighted(_) | Self::MaxWeighted(_) => self
+                .rank_facets_within(counter, active, among, ties, budget)
+                .into_iter()
+                .next(),
+            Self::MaxWeightedFacetCounting(_) => {
+                let total = counter.count(&Weight::FacetCounting, active);
                 let bound = Some(0);
-                let (mut curr, mut f): (Option<usize>, Option<String>) =
-                    (prev_count.map(|c| c - 1).or(Some(usize::MAX)), None);
-                for facet in among.iter() {
-                    active.push(facet.clone());
-                    counted = count(&mut Weight::FacetCounting, nav, active.iter());
-                    if counted == bound {
-                        return Some((facet.to_owned(), bound));
-                    }
-                    if counted.zip(curr).is_some_and(|(x, y)| x <= y) {
-                        curr = counted;
-                        f = Some(facet.to_owned());
-                    }
-                    active.pop();
-
-                    let exc_facet = format!("~{facet}");
-                    active.push(exc_facet.clone());
-                    counted = count(&mut Weight::FacetCounting, nav, active.iter());
-                    if counted == bound {
-                        return Some((exc_facet.to_owned(), bound));
-                    }
-                    if counted.zip(curr).is_some_and(|(x, y)| x <= y) {
-                        curr = counted;
-                        f = Some(exc_facet);
-                    }
-                    active.pop();
-                }
+                let (mut curr, mut f): (Option<usize>, Option<String>) = (
+                    total.map(|c| c.saturating_sub(1)).or(Some(usize::MAX)),
+                    None,
+                );
+                let mut hit = None;
+                score(
+                    counter,
+                    |c, routes| c.count_all(&Weight::FacetCounting, routes),
+                    active,
+                    among,
+                    budget,
+                    |facet| vec![facet.to_owned(), format!("~{facet}")],
+                    |_, scored| {
+                        for (lit, counted) in scored {
+                            if counted == bound {
+                                hit = Some(lit);
+                                return false;
+                            }
+                            if counted.zip(curr).is_some_and(|(x, y)| x <= y) {
+                                curr = counted;
+                                f = Some(lit);
+                            }
+                        }
+                        true
+                    },
+                );
 
-                f.zip(Some(curr))
+                match hit {
+                    Some(lit) => Some((lit, bound)),
+                    _ => f.zip(Some(curr)),
+                }
             }
-            Self::MinWeightedFacetCounting(prev_count) => {
-                let mut counted;
-                let bound = prev_count.map(|c| c - 1).or(Some(usize::MAX));
+            Self::MinWeightedFacetCounting(_) => {
+                let total = counter.count(&Weight::FacetCounting, active);
+                let bound = total.map(|c| c.saturating_sub(1)).or(Some(usize::MAX));
                 let (mut curr, mut f): (Option<usize>, Option<String>) = (Some(0), None);
-                for facet in among.iter() {
-                    let exc_facet = format!("~{facet}");
-                    active.push(exc_facet.clone());
-                    counted = count(&mut Weight::FacetCounting, nav, active.iter());
-                    if counted == bound {
-                        return Some((exc_facet.to_owned(), bound));
-                    }
-                    if counted.zip(curr).is_some_and(|(x, y)| x >= y) {
-                        curr = counted;
-                        f = Some(exc_facet);
-                    }
-                    active.pop();
-
-                    active.push(facet.clone());
-                    counted = count(&mut Weight::FacetCounting, nav, active.iter());
-                    if counted == bound {
-                        return Some((facet.to_owned(), bound));
-                    }
-                    if counted.zip(curr).is_some_and(|(x, y)| x >= y) {
-                        curr = counted;
-                        f = Some(facet.to_owned());
-                    }
-                    active.pop();
-                }
+                let mut hit = None;
+                score(
+                    counter,
+                    |c, routes| c.count_all(&Weight::FacetCounting, routes),
+                    active,
+                    among,
+                    budget,
+                    |facet| vec![format!("~{facet}"), facet.to_owned()],
+                    |_, scored| {
+                        for (lit, counted) in scored {
+                            if counted == bound {
+                                hit = Some(lit);
+                                return false;
+                            }
+                            if counted.zip(curr).is_some_and(|(x, y)| x >= y) {
+                                curr = counted;
+                                f = Some(lit);
+                            }
+                        }
+                        true
+                    },
+                );
 
-                f.zip(Some(curr))
+                match hit {
+                    Some(lit) => Some((lit, bound)),
+                    _ => f.zip(Some(curr)),
+                }
             }
-            Self::MaxWeightedAnswerSetCounting(prev_count) => {
-                let mut counted;
+            Self::MaxWeightedAnswerSetCounting(_) => {
+                let total = counter.count(&Weight::AnswerSetCounting, active);
                 let bound = Some(1);
-                let (mut curr, mut f): (Option<usize>, Option<String>) =
-                    (prev_count.map(|c| c - 1).or(Some(usize::MAX)), None);
-                for facet in among.iter() {
-                    active.push(facet.clone());
-                    counted = count(&mut Weight::AnswerSetCounting, nav, active.iter());
-                    if counted == bound {
-                        return Some((facet.to_owned(), bound));
-                    }
-                    if counted.zip(curr).is_some_and(|(x, y)| x <= y) {
-                        curr = counted;
-                        f = Some(facet.to_owned());
-                    }
-                    active.pop();
-
-                    counted = prev_count.zip(counted).map(|(x, y)| x - y);
-                    if counted == bound {
-                        return Some((format!("~{facet}"), bound));
-                    }
-                    if counted.zip(curr).is_some_and(|(x, y)| x <= y) {
-                        curr = counted;
-                        f = Some(format!("~{facet}"));
-                    }
-                }
+                let (mut curr, mut f): (Option<usize>, Option<String>) = (
+                    total.map(|c| c.saturating_sub(1)).or(Some(usize::MAX)),
+                    None,
+                );
+                let mut hit = None;
+                score_answer_sets(
+                    counter,
+                    total,
+                    active,
+                    among,
+                    budget,
+                    |facet| [facet.to_owned(), format!("~{facet}")],
+                    |_, scored| {
+                        for (lit, counted) in scored {
+                            if counted == bound {
+                                hit = Some(lit);
+                                return false;
+                            }
+                            if counted.zip(curr).is_some_and(|(x, y)| x <= y) {
+                                curr = counted;
+                                f = Some(lit);
+                            }
+                        }
+                        true
+                    },
+                );
 
-                f.zip(Some(curr))
+                match hit {
+                    Some(lit) => Some((lit, bound)),
+                    _ => f.zip(Some(curr)),
+                }
             }
-            Self::MinWeightedAnswerSetCounting(prev_count) => {
-                let mut counted;
-                let bound = prev_count.map(|c| c - 1).or(Some(usize::MAX));
+            Self::MinWeightedAnswerSetCounting(_) => {
+                let total = counter.count(&Weight::AnswerSetCounting, active);
+                let bound = total.map(|c| c.saturating_sub(1)).or(Some(usize::MAX));
                 let (mut curr, mut f): (Option<usize>, Option<String>) = (Some(1), None);
-                for facet in among.iter() {
-                    let exc_facet = format!("~{facet}");
-                    active.push(exc_facet.clone());
-                    counted = count(&mut Weight::AnswerSetCounting, nav, active.iter());
-                    if counted == bound {
-                        return Some((exc_facet.to_string(), bound));
-                    }
-                    if counted.zip(curr).is_some_and(|(x, y)| x >= y) {
-                        curr = counted;
-                        f = Some(exc_facet.to_owned());
-                    }
-                    active.pop();
-
-                    counted = prev_count.zip(counted).map(|(x, y)| x - y);
-                    if counted == bound {
-                        return Some((facet.to_owned(), bound));
-                    }
-                    if counted.zip(curr).is_some_and(|(x, y)| x >= y) {
-                        curr = counted;
-                        f = Some(facet.to_owned());
-                    }
+                let mut hit = None;
+                score_answer_sets(
+                    counter,
+                    total,
+                    active,
+                    among,
+                    budget,
+                    |facet| [format!("~{facet}"), facet.to_owned()],
+                    |_, scored| {
+                        for (lit, counted) in scored {
+                            if counted == bound {
+                                hit = Some(lit);
+                                return false;
+                            }
+                            if counted.zip(curr).is_some_and(|(x, y)| x >= y) {
+                                curr = counted;
+                                f = Some(lit);
+                            }
+                        }
+                        true
+                    },
+                );
+
+                match hit {
+                    Some(lit) => Some((lit, bound)),
+                    _ => f.zip(Some(curr)),
                 }
+            }
+        }
+    }
+
+    fn rank_facets_within(
+        &self,
+        counter: &mut Counter,
+        active: &[String],
+        among: &[String],
+        ties: &TieBreak,
+        budget: &Budget,
+    ) -> Vec<(String, Option<usize>)> {
+        let mut ranked = vec![];
+        match self {
+            Self::GoalOriented(_) => {
+                let mut ranked = among
+                    .iter()
+                    .map(|f| (f.to_string(), None))
+                    .collect::<Vec<_>>();
+                ranked.sort_by(|(x, _), (y, _)| ties.cmp(x, y));
+                return ranked;
+            }
+            Self::Random(seed) => {
+                // NOTE: independent of the order of facets, hence reproducible for a seed
+                let (seed, at) = (seed.unwrap_or_default() as u64, active.join(" "));
+                let mut ranked = among
+                    .iter()
+                    .flat_map(|f| [f.to_owned(), format!("~{f}")])
+                    .map(|l| (l, None))
+                    .collect::<Vec<_>>();
+                ranked.sort_by_cached_key(|(l, _)| (mix(seed, &format!("{at} {l}")), l.clone()));
+                return ranked;
+            }
+            Self::MinWeighted(_) | Self::MaxWeighted(_) => {
+                return rank_weighted(self, counter, active, among, ties, budget)
+                    .into_iter()
+                    .map(|(l, _)| (l, None))
+                    .collect();
+            }
+            Self::MaxWeightedFacetCounting(_) | Self::MinWeightedFacetCounting(_) => score(
+                counter,
+                |c, routes| c.count_all(&Weight::FacetCounting, routes),
+                active,
+                among,
+                budget,
+                |facet| vec![facet.to_owned(), format!("~{facet}")],
+                |_, scored| {
+                    ranked.extend(scored);
+                    true
+                },
+            ),
+            Self::MaxWeightedAnswerSetCounting(_) | Self::MinWeightedAnswerSetCounting(_) => {
+                let total = counter.count(&Weight::AnswerSetCounting, active);
+                score_answer_sets(
+                    counter,
+                    total,
+                    active,
+                    among,
+                    budget,
+                    |facet| [facet.to_owned(), format!("~{facet}")],
+                    |_, scored| {
+                        ranked.extend(scored);
+                        true
+                    },
+                )
+            }
+        }
 
-                f.zip(Some(curr))
+        ranked.retain(|(_, c)| c.is_some());
+        match self {
+            Self::MaxWeightedFacetCounting(_) | Self::MaxWeightedAnswerSetCounting(_) => {
+                ranked.sort_by(|(x, c), (y, d)| c.cmp(d).then_with(|| ties.cmp(x, y)))
             }
+            _ => ranked.sort_by(|(x, c), (y, d)| d.cmp(c).then_with(|| ties.cmp(x, y))),
         }
+
+        ranked
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures::TINY;
     use savan::lex::repr;
     use savan::nav::{errors::*, facets::*, Navigator};
 
-    const TINY: &'static str = "a;b. c;d :- b. e.";
-    const Q8: &'static str = "
+    const Q8: &str = "
     {q(I ,1..8)} == 1 :- I = 1..8. 
     {q(1..8, J)} == 1 :- J = 1..8. 
     :- {q(D-J, J)} >= 2, D = 2..2*8. 
     :- {q(D+J, J)} >= 2, D = 1-8..8-1.";
-    const NONTIGHT: &'static str = "a :- b. b :- a. a :- c. c :- not d. d :- not c.";
+    const NONTIGHT: &str = "a :- b. b :- a. a :- c. c :- not d. d :- not c.";
 
     #[test]
     fn tiny_max_fc() -> Result<()> {
         let mut nav = Navigator::new(TINY, vec!["0".to_string()])?;
         for _ in 0..9 {
-            let mut active = vec![];
+            let active: [String; 0] = [];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = Some(among.len());
-            if let Some((f, c)) =
-                Mode::MaxWeightedFacetCounting(count).propose_facet(&mut nav, &mut active, &among)
-            {
+            if let Some((f, c)) = Mode::MaxWeightedFacetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
+                &among,
+                &TieBreak::default(),
+                &Budget::default(),
+            ) {
                 assert_eq!(c, Some(0));
-                assert!(vec!["a", "c", "d", "~b"].contains(&f.as_str()));
+                assert!(["a", "c", "d", "~b"].contains(&f.as_str()));
             } else {
                 panic!()
             }
         }
         for _ in 0..9 {
-            let mut active = vec!["b".to_string()];
+            let active = ["b".to_string()];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = Some(among.len());
-            if let Some((f, c)) =
-                Mode::MaxWeightedFacetCounting(count).propose_facet(&mut nav, &mut active, &among)
-            {
+            if let Some((f, c)) = Mode::MaxWeightedFacetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
+                &among,
+                &TieBreak::default(),
+                &Budget::default(),
+            ) {
                 assert_eq!(c, Some(0));
-                assert!(vec!["c", "d", "~c", "~d"].contains(&f.as_str()));
+                assert!(["c", "d", "~c", "~d"].contains(&f.as_str()));
             } else {
                 panic!()
             }
         }
         for _ in 0..9 {
-            let mut active = vec!["a".to_string()];
+            let active = ["a".to_string()];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = Some(among.len());
             assert_eq!(
-                Mode::MaxWeightedFacetCounting(count).propose_facet(&mut nav, &mut active, &among),
+                Mode::MaxWeightedFacetCounting(count).propose_facet_within(
+                    &mut Counter::new(&mut nav, None),
+                    &active,
+                    &among,
+                    &TieBreak::default(),
+                    &Budget::default()
+                ),
                 None
             );
         }
@@ -294,34 +681,42 @@ mod tests {
     fn nontight_max_fc() -> Result<()> {
         let mut nav = Navigator::new(NONTIGHT, vec!["0".to_string(), "--supp-models".to_string()])?;
         for _ in 0..1 {
-            let mut active = vec![];
+            let active: [String; 0] = [];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = Some(among.len());
-            if let Some((f, c)) =
-                Mode::MaxWeightedFacetCounting(count).propose_facet(&mut nav, &mut active, &among)
-            {
+            if let Some((f, c)) = Mode::MaxWeightedFacetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
+                &among,
+                &TieBreak::default(),
+                &Budget::default(),
+            ) {
                 assert_eq!(c, Some(0));
-                assert!(vec!["c", "~d", "~a", "~b"].contains(&f.as_str()));
+                assert!(["c", "~d", "~a", "~b"].contains(&f.as_str()));
             } else {
                 panic!()
             }
         }
         let mut nav = Navigator::new(NONTIGHT, vec!["0".to_string()])?;
         for _ in 0..4 {
-            let mut active = vec![];
+            let active: [String; 0] = [];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = Some(among.len());
-            if let Some((f, c)) =
-                Mode::MaxWeightedFacetCounting(count).propose_facet(&mut nav, &mut active, &among)
-            {
+            if let Some((f, c)) = Mode::MaxWeightedFacetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
+                &among,
+                &TieBreak::default(),
+                &Budget::default(),
+            ) {
                 assert_eq!(c, Some(0));
-                assert!(vec!["a", "b", "c", "d", "~a", "~b", "~c", "~d"].contains(&f.as_str()));
+                assert!(["a", "b", "c", "d", "~a", "~b", "~c", "~d"].contains(&f.as_str()));
             } else {
                 panic!()
             }
@@ -334,33 +729,41 @@ mod tests {
     fn q8_max_fc() -> Result<()> {
         let mut nav = Navigator::new(Q8, vec!["0".to_string()])?;
         for _ in 0..9 {
-            let mut active = vec![];
+            let active: [String; 0] = [];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = Some(among.len());
-            if let Some((f, c)) =
-                Mode::MaxWeightedFacetCounting(count).propose_facet(&mut nav, &mut active, &among)
-            {
+            if let Some((f, c)) = Mode::MaxWeightedFacetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
+                &among,
+                &TieBreak::default(),
+                &Budget::default(),
+            ) {
                 assert_eq!(c, Some(40));
-                assert!(vec!["q(3,3)", "q(6,6)", "q(3,6)", "q(6,3)"].contains(&f.as_str()));
+                assert!(["q(3,3)", "q(6,6)", "q(3,6)", "q(6,3)"].contains(&f.as_str()));
             } else {
                 panic!()
             }
         }
         for _ in 0..24 {
-            let mut active = vec!["q(6,6)".to_string()];
+            let active = ["q(6,6)".to_string()];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = Some(among.len());
-            if let Some((f, c)) =
-                Mode::MaxWeightedFacetCounting(count).propose_facet(&mut nav, &mut active, &among)
-            {
+            if let Some((f, c)) = Mode::MaxWeightedFacetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
+                &among,
+                &TieBreak::default(),
+                &Budget::default(),
+            ) {
                 assert_eq!(c, Some(0));
-                assert!(vec![
+                assert!([
                     "q(4,1)", "q(3,5)", "q(8,5)", "q(4,7)", "q(5,3)", "q(7,8)", "q(1,3)", "q(1,4)",
                     "q(8,7)", "q(5,8)", "q(3,1)", "q(7,4)"
                 ]
@@ -370,17 +773,21 @@ mod tests {
             }
         }
         for _ in 0..30 {
-            let mut active = vec!["q(2,8)".to_string()];
+            let active = ["q(2,8)".to_string()];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = Some(among.len());
-            if let Some((f, c)) =
-                Mode::MaxWeightedFacetCounting(count).propose_facet(&mut nav, &mut active, &among)
-            {
+            if let Some((f, c)) = Mode::MaxWeightedFacetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
+                &among,
+                &TieBreak::default(),
+                &Budget::default(),
+            ) {
                 assert_eq!(c, Some(0));
-                assert!(vec![
+                assert!([
                     "q(4,7)", "q(1,2)", "q(6,5)", "q(8,4)", "q(3,5)", "q(4,5)", "q(8,7)", "q(1,6)",
                     "q(1,3)", "q(5,6)", "q(8,6)", "q(3,6)", "q(3,2)", "q(4,4)", "q(7,5)"
                 ]
@@ -397,46 +804,60 @@ mod tests {
     fn tiny_min_fc() -> Result<()> {
         let mut nav = Navigator::new(TINY, vec!["0".to_string()])?;
         for _ in 0..9 {
-            let mut active = vec![];
+            let active: [String; 0] = [];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = Some(among.len());
-            if let Some((f, c)) =
-                Mode::MinWeightedFacetCounting(count).propose_facet(&mut nav, &mut active, &among)
-            {
+            if let Some((f, c)) = Mode::MinWeightedFacetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
+                &among,
+                &TieBreak::default(),
+                &Budget::default(),
+            ) {
                 assert_eq!(c, Some(6));
-                assert!(vec!["~c", "~d"].contains(&f.as_str()));
+                assert!(["~c", "~d"].contains(&f.as_str()));
             } else {
                 panic!()
             }
         }
         for _ in 0..9 {
-            let mut active = vec!["b".to_string()];
+            let active = ["b".to_string()];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = Some(among.len());
-            if let Some((f, c)) =
-                Mode::MinWeightedFacetCounting(count).propose_facet(&mut nav, &mut active, &among)
-            {
+            if let Some((f, c)) = Mode::MinWeightedFacetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
+                &among,
+                &TieBreak::default(),
+                &Budget::default(),
+            ) {
                 assert_eq!(c, Some(0));
-                assert!(vec!["c", "d", "~c", "~d"].contains(&f.as_str()));
+                assert!(["c", "d", "~c", "~d"].contains(&f.as_str()));
             } else {
                 panic!()
             }
         }
         for _ in 0..9 {
-            let mut active = vec!["a".to_string()];
+            let active = ["a".to_string()];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = Some(among.len());
             assert_eq!(
-                Mode::MinWeightedFacetCounting(count).propose_facet(&mut nav, &mut active, &among),
+                Mode::MinWeightedFacetCounting(count).propose_facet_within(
+                    &mut Counter::new(&mut nav, None),
+                    &active,
+                    &among,
+                    &TieBreak::default(),
+                    &Budget::default()
+                ),
                 None
             );
         }
@@ -448,34 +869,42 @@ mod tests {
     fn nontight_min_fc() -> Result<()> {
         let mut nav = Navigator::new(NONTIGHT, vec!["0".to_string(), "--supp-models".to_string()])?;
         for _ in 0..4 {
-            let mut active = vec![];
+            let active: [String; 0] = [];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = Some(among.len());
-            if let Some((f, c)) =
-                Mode::MinWeightedFacetCounting(count).propose_facet(&mut nav, &mut active, &among)
-            {
+            if let Some((f, c)) = Mode::MinWeightedFacetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
+                &among,
+                &TieBreak::default(),
+                &Budget::default(),
+            ) {
                 assert_eq!(c, Some(4));
-                assert!(vec!["a", "b", "~c", "d"].contains(&f.as_str()));
+                assert!(["a", "b", "~c", "d"].contains(&f.as_str()));
             } else {
                 panic!()
             }
         }
         let mut nav = Navigator::new(NONTIGHT, vec!["0".to_string()])?;
         for _ in 0..16 {
-            let mut active = vec![];
+            let active: [String; 0] = [];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = Some(among.len());
-            if let Some((f, c)) =
-                Mode::MinWeightedFacetCounting(count).propose_facet(&mut nav, &mut active, &among)
-            {
+            if let Some((f, c)) = Mode::MinWeightedFacetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
+                &among,
+                &TieBreak::default(),
+                &Budget::default(),
+            ) {
                 assert_eq!(c, Some(0));
-                assert!(vec!["a", "b", "c", "d", "~a", "~b", "~c", "~d"].contains(&f.as_str()));
+                assert!(["a", "b", "c", "d", "~a", "~b", "~c", "~d"].contains(&f.as_str()));
             } else {
                 panic!()
             }
@@ -488,17 +917,21 @@ mod tests {
     fn q8_min_fc() -> Result<()> {
         let mut nav = Navigator::new(Q8, vec!["0".to_string()])?;
         for _ in 0..70 {
-            let mut active = vec![];
+            let active: [String; 0] = [];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = Some(among.len());
-            if let Some((f, c)) =
-                Mode::MinWeightedFacetCounting(count).propose_facet(&mut nav, &mut active, &among)
-            {
+            if let Some((f, c)) = Mode::MinWeightedFacetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
+                &among,
+                &TieBreak::default(),
+                &Budget::default(),
+            ) {
                 assert_eq!(c, Some(126));
-                assert!(vec![
+                assert!([
                     "~q(3,1)", "~q(8,6)", "~q(4,8)", "~q(5,7)", "~q(3,2)", "~q(5,6)", "~q(2,2)",
                     "~q(2,4)", "~q(3,4)", "~q(4,3)", "~q(1,2)", "~q(7,1)", "~q(5,8)", "~q(7,4)",
                     "~q(4,1)", "~q(2,5)", "~q(4,6)", "~q(2,7)", "~q(7,2)", "~q(6,1)", "~q(5,2)",
@@ -516,17 +949,21 @@ mod tests {
             }
         }
         for _ in 0..22 {
-            let mut active = vec!["q(6,6)".to_string()];
+            let active = ["q(6,6)".to_string()];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = Some(among.len());
-            if let Some((f, c)) =
-                Mode::MinWeightedFacetCounting(count).propose_facet(&mut nav, &mut active, &among)
-            {
+            if let Some((f, c)) = Mode::MinWeightedFacetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
+                &among,
+                &TieBreak::default(),
+                &Budget::default(),
+            ) {
                 assert_eq!(c, Some(34));
-                assert!(vec![
+                assert!([
                     "~q(8,7)", "~q(3,5)", "~q(4,1)", "~q(1,4)", "~q(1,3)", "~q(5,8)", "~q(3,1)",
                     "~q(8,5)", "~q(4,7)", "~q(7,4)", "~q(5,3)", "~q(7,8)",
                 ]
@@ -536,17 +973,21 @@ mod tests {
             }
         }
         for _ in 0..6 {
-            let mut active = vec!["q(2,8)".to_string()];
+            let active = ["q(2,8)".to_string()];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = Some(among.len());
-            if let Some((f, c)) =
-                Mode::MinWeightedFacetCounting(count).propose_facet(&mut nav, &mut active, &among)
-            {
+            if let Some((f, c)) = Mode::MinWeightedFacetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
+                &among,
+                &TieBreak::default(),
+                &Budget::default(),
+            ) {
                 assert_eq!(c, Some(62));
-                assert!(vec!["~q(4,5)", "~q(8,7)", "~q(5,6)",].contains(&f.as_str()));
+                assert!(["~q(4,5)", "~q(8,7)", "~q(5,6)",].contains(&f.as_str()));
             } else {
                 panic!()
             }
@@ -559,53 +1000,59 @@ mod tests {
     fn tiny_max_as() -> Result<()> {
         let mut nav = Navigator::new(TINY, vec!["0".to_string()])?;
         for _ in 0..9 {
-            let mut active = vec![];
+            let active: [String; 0] = [];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = nav.enumerate_solutions_quietly(None, active.iter()).ok();
-            if let Some((f, c)) = Mode::MaxWeightedAnswerSetCounting(count).propose_facet(
-                &mut nav,
-                &mut active,
+            if let Some((f, c)) = Mode::MaxWeightedAnswerSetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
                 &among,
+                &TieBreak::default(),
+                &Budget::default(),
             ) {
                 assert_eq!(c, Some(1));
-                assert!(vec!["a", "c", "d", "~b"].contains(&f.as_str()));
+                assert!(["a", "c", "d", "~b"].contains(&f.as_str()));
             } else {
                 panic!()
             }
         }
         for _ in 0..9 {
-            let mut active = vec!["b".to_string()];
+            let active = ["b".to_string()];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = nav.enumerate_solutions_quietly(None, active.iter()).ok();
-            if let Some((f, c)) = Mode::MaxWeightedAnswerSetCounting(count).propose_facet(
-                &mut nav,
-                &mut active,
+            if let Some((f, c)) = Mode::MaxWeightedAnswerSetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
                 &among,
+                &TieBreak::default(),
+                &Budget::default(),
             ) {
                 assert_eq!(c, Some(1));
-                assert!(vec!["c", "d", "~c", "~d"].contains(&f.as_str()));
+                assert!(["c", "d", "~c", "~d"].contains(&f.as_str()));
             } else {
                 panic!()
             }
         }
         for _ in 0..9 {
-            let mut active = vec!["a".to_string()];
+            let active = ["a".to_string()];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = nav.enumerate_solutions_quietly(None, active.iter()).ok();
             assert_eq!(
-                Mode::MaxWeightedAnswerSetCounting(count).propose_facet(
-                    &mut nav,
-                    &mut active,
-                    &among
+                Mode::MaxWeightedAnswerSetCounting(count).propose_facet_within(
+                    &mut Counter::new(&mut nav, None),
+                    &active,
+                    &among,
+                    &TieBreak::default(),
+                    &Budget::default()
                 ),
                 None
             );
@@ -618,38 +1065,42 @@ mod tests {
     fn nontight_max_as() -> Result<()> {
         let mut nav = Navigator::new(NONTIGHT, vec!["0".to_string(), "--supp-models".to_string()])?;
         for _ in 0..4 {
-            let mut active = vec![];
+            let active: [String; 0] = [];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = nav.enumerate_solutions_quietly(None, active.iter()).ok();
-            if let Some((f, c)) = Mode::MaxWeightedAnswerSetCounting(count).propose_facet(
-                &mut nav,
-                &mut active,
+            if let Some((f, c)) = Mode::MaxWeightedAnswerSetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
                 &among,
+                &TieBreak::default(),
+                &Budget::default(),
             ) {
                 assert_eq!(c, Some(1));
-                assert!(vec!["c", "~d", "~a", "~b"].contains(&f.as_str()));
+                assert!(["c", "~d", "~a", "~b"].contains(&f.as_str()));
             } else {
                 panic!()
             }
         }
         let mut nav = Navigator::new(NONTIGHT, vec!["0".to_string()])?;
         for _ in 0..16 {
-            let mut active = vec![];
+            let active: [String; 0] = [];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = nav.enumerate_solutions_quietly(None, active.iter()).ok();
-            if let Some((f, c)) = Mode::MaxWeightedAnswerSetCounting(count).propose_facet(
-                &mut nav,
-                &mut active,
+            if let Some((f, c)) = Mode::MaxWeightedAnswerSetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
                 &among,
+                &TieBreak::default(),
+                &Budget::default(),
             ) {
                 assert_eq!(c, Some(1));
-                assert!(vec!["a", "b", "c", "d", "~a", "~b", "~c", "~d"].contains(&f.as_str()));
+                assert!(["a", "b", "c", "d", "~a", "~b", "~c", "~d"].contains(&f.as_str()));
             } else {
                 panic!()
             }
@@ -662,19 +1113,21 @@ mod tests {
     fn q8_max_as() -> Result<()> {
         let mut nav = Navigator::new(Q8, vec!["0".to_string()])?;
         for _ in 0..16 {
-            let mut active = vec![];
+            let active: [String; 0] = [];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = nav.enumerate_solutions_quietly(None, active.iter()).ok();
-            if let Some((f, c)) = Mode::MaxWeightedAnswerSetCounting(count).propose_facet(
-                &mut nav,
-                &mut active,
+            if let Some((f, c)) = Mode::MaxWeightedAnswerSetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
                 &among,
+                &TieBreak::default(),
+                &Budget::default(),
             ) {
                 assert_eq!(c, Some(4));
-                assert!(vec![
+                assert!([
                     "q(3,3)", "q(6,6)", "q(3,6)", "q(6,3)", "q(1,1)", "q(1,8)", "q(8,1)", "q(8,8)"
                 ]
                 .contains(&f.as_str()));
@@ -683,19 +1136,21 @@ mod tests {
             }
         }
         for _ in 0..24 {
-            let mut active = vec!["q(6,6)".to_string()];
+            let active = ["q(6,6)".to_string()];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = nav.enumerate_solutions_quietly(None, active.iter()).ok();
-            if let Some((f, c)) = Mode::MaxWeightedAnswerSetCounting(count).propose_facet(
-                &mut nav,
-                &mut active,
+            if let Some((f, c)) = Mode::MaxWeightedAnswerSetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
                 &among,
+                &TieBreak::default(),
+                &Budget::default(),
             ) {
                 assert_eq!(c, Some(1));
-                assert!(vec![
+                assert!([
                     "q(3,1)", "q(1,3)", "q(4,1)", "q(1,4)", "q(4,7)", "q(7,4)", "q(8,5)", "q(5,8)",
                     "q(3,5)", "q(5,3)", "q(7,8)", "q(8,7)"
                 ]
@@ -705,19 +1160,21 @@ mod tests {
             }
         }
         for _ in 0..30 {
-            let mut active = vec!["q(2,8)".to_string()];
+            let active = ["q(2,8)".to_string()];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = nav.enumerate_solutions_quietly(None, active.iter()).ok();
-            if let Some((f, c)) = Mode::MaxWeightedAnswerSetCounting(count).propose_facet(
-                &mut nav,
-                &mut active,
+            if let Some((f, c)) = Mode::MaxWeightedAnswerSetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
                 &among,
+                &TieBreak::default(),
+                &Budget::default(),
             ) {
                 assert_eq!(c, Some(1));
-                assert!(vec![
+                assert!([
                     "q(4,7)", "q(1,2)", "q(6,5)", "q(8,4)", "q(3,5)", "q(4,5)", "q(8,7)", "q(1,6)",
                     "q(1,3)", "q(5,6)", "q(8,6)", "q(3,6)", "q(3,2)", "q(4,4)", "q(7,5)"
                 ]
@@ -734,53 +1191,59 @@ mod tests {
     fn tiny_min_as() -> Result<()> {
         let mut nav = Navigator::new(TINY, vec!["0".to_string()])?;
         for _ in 0..9 {
-            let mut active = vec![];
+            let active: [String; 0] = [];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = nav.enumerate_solutions_quietly(None, active.iter()).ok();
-            if let Some((f, c)) = Mode::MinWeightedAnswerSetCounting(count).propose_facet(
-                &mut nav,
-                &mut active,
+            if let Some((f, c)) = Mode::MinWeightedAnswerSetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
                 &among,
+                &TieBreak::default(),
+                &Budget::default(),
             ) {
                 assert_eq!(c, Some(2));
-                assert!(vec!["~a", "~c", "~d", "b"].contains(&f.as_str()));
+                assert!(["~a", "~c", "~d", "b"].contains(&f.as_str()));
             } else {
                 panic!()
             }
         }
         for _ in 0..9 {
-            let mut active = vec!["b".to_string()];
+            let active = ["b".to_string()];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = nav.enumerate_solutions_quietly(None, active.iter()).ok();
-            if let Some((f, c)) = Mode::MinWeightedAnswerSetCounting(count).propose_facet(
-                &mut nav,
-                &mut active,
+            if let Some((f, c)) = Mode::MinWeightedAnswerSetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
                 &among,
+                &TieBreak::default(),
+                &Budget::default(),
             ) {
                 assert_eq!(c, Some(1));
-                assert!(vec!["c", "d", "~c", "~d"].contains(&f.as_str()));
+                assert!(["c", "d", "~c", "~d"].contains(&f.as_str()));
             } else {
                 panic!()
             }
         }
         for _ in 0..9 {
-            let mut active = vec!["a".to_string()];
+            let active = ["a".to_string()];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = nav.enumerate_solutions_quietly(None, active.iter()).ok();
             assert_eq!(
-                Mode::MinWeightedAnswerSetCounting(count).propose_facet(
-                    &mut nav,
-                    &mut active,
-                    &among
+                Mode::MinWeightedAnswerSetCounting(count).propose_facet_within(
+                    &mut Counter::new(&mut nav, None),
+                    &active,
+                    &among,
+                    &TieBreak::default(),
+                    &Budget::default()
                 ),
                 None
             );
@@ -793,38 +1256,42 @@ mod tests {
     fn nontight_min_as() -> Result<()> {
         let mut nav = Navigator::new(NONTIGHT, vec!["0".to_string(), "--supp-models".to_string()])?;
         for _ in 0..4 {
-            let mut active = vec![];
+            let active: [String; 0] = [];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = nav.enumerate_solutions_quietly(None, active.iter()).ok();
-            if let Some((f, c)) = Mode::MinWeightedAnswerSetCounting(count).propose_facet(
-                &mut nav,
-                &mut active,
+            if let Some((f, c)) = Mode::MinWeightedAnswerSetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
                 &among,
+                &TieBreak::default(),
+                &Budget::default(),
             ) {
                 assert_eq!(c, Some(2));
-                assert!(vec!["~c", "d", "a", "b"].contains(&f.as_str()));
+                assert!(["~c", "d", "a", "b"].contains(&f.as_str()));
             } else {
                 panic!()
             }
         }
         let mut nav = Navigator::new(NONTIGHT, vec!["0".to_string()])?;
         for _ in 0..16 {
-            let mut active = vec![];
+            let active: [String; 0] = [];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = nav.enumerate_solutions_quietly(None, active.iter()).ok();
-            if let Some((f, c)) = Mode::MinWeightedAnswerSetCounting(count).propose_facet(
-                &mut nav,
-                &mut active,
+            if let Some((f, c)) = Mode::MinWeightedAnswerSetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
                 &among,
+                &TieBreak::default(),
+                &Budget::default(),
             ) {
                 assert_eq!(c, Some(1));
-                assert!(vec!["a", "b", "c", "d", "~a", "~b", "~c", "~d"].contains(&f.as_str()));
+                assert!(["a", "b", "c", "d", "~a", "~b", "~c", "~d"].contains(&f.as_str()));
             } else {
                 panic!()
             }
@@ -837,19 +1304,21 @@ mod tests {
     fn q8_min_as() -> Result<()> {
         let mut nav = Navigator::new(Q8, vec!["0".to_string()])?;
         for _ in 0..16 {
-            let mut active = vec![];
+            let active: [String; 0] = [];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = nav.enumerate_solutions_quietly(None, active.iter()).ok();
-            if let Some((f, c)) = Mode::MinWeightedAnswerSetCounting(count).propose_facet(
-                &mut nav,
-                &mut active,
+            if let Some((f, c)) = Mode::MinWeightedAnswerSetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
                 &among,
+                &TieBreak::default(),
+                &Budget::default(),
             ) {
                 assert_eq!(c, Some(88));
-                assert!(vec![
+                assert!([
                     "q(3,3)", "q(6,6)", "q(3,6)", "q(6,3)", "q(1,1)", "q(1,8)", "q(8,1)", "q(8,8)"
                 ]
                 .iter()
@@ -861,19 +1330,21 @@ mod tests {
             }
         }
         for _ in 0..24 {
-            let mut active = vec!["q(6,6)".to_string()];
+            let active = ["q(6,6)".to_string()];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = nav.enumerate_solutions_quietly(None, active.iter()).ok();
-            if let Some((f, c)) = Mode::MinWeightedAnswerSetCounting(count).propose_facet(
-                &mut nav,
-                &mut active,
+            if let Some((f, c)) = Mode::MinWeightedAnswerSetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
                 &among,
+                &TieBreak::default(),
+                &Budget::default(),
             ) {
                 assert_eq!(c, Some(3));
-                assert!(vec![
+                assert!([
                     "q(3,1)", "q(1,3)", "q(4,1)", "q(1,4)", "q(4,7)", "q(7,4)", "q(8,5)", "q(5,8)",
                     "q(3,5)", "q(5,3)", "q(7,8)", "q(8,7)"
                 ]
@@ -886,19 +1357,21 @@ mod tests {
             }
         }
         for _ in 0..30 {
-            let mut active = vec!["q(2,8)".to_string()];
+            let active = ["q(2,8)".to_string()];
             let among = nav
                 .facet_inducing_atoms(active.iter())
                 .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
                 .ok_or(NavigatorError::None)?;
             let count = nav.enumerate_solutions_quietly(None, active.iter()).ok();
-            if let Some((f, c)) = Mode::MinWeightedAnswerSetCounting(count).propose_facet(
-                &mut nav,
-                &mut active,
+            if let Some((f, c)) = Mode::MinWeightedAnswerSetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
                 &among,
+                &TieBreak::default(),
+                &Budget::default(),
             ) {
                 assert_eq!(c, Some(7));
-                assert!(vec![
+                assert!([
                     "q(4,7)", "q(1,2)", "q(6,5)", "q(8,4)", "q(3,5)", "q(4,5)", "q(8,7)", "q(1,6)",
                     "q(1,3)", "q(5,6)", "q(8,6)", "q(3,6)", "q(3,2)", "q(4,4)", "q(7,5)"
                 ]
@@ -911,4 +1384,322 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn tiny_ranked() -> Result<()> {
+        let mut nav = Navigator::new(TINY, vec!["0".to_string()])?;
+        let active: [String; 0] = [];
+        let among = nav
+            .facet_inducing_atoms(active.iter())
+            .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
+            .ok_or(NavigatorError::None)?;
+        let count = Some(2 * among.len());
+        let mut counter = Counter::new(&mut nav, None);
+
+        let ranked = Mode::MaxWeightedFacetCounting(count).rank_facets_within(
+            &mut counter,
+            &active,
+            &among,
+            &TieBreak::default(),
+            &Budget::default(),
+        );
+        assert_eq!(ranked.len(), 2 * among.len());
+        assert!(ranked.windows(2).all(|w| w[0].1 <= w[1].1));
+        assert_eq!(ranked[0].1, Some(0));
+
+        let ranked = Mode::MinWeightedFacetCounting(count).rank_facets_within(
+            &mut counter,
+            &active,
+            &among,
+            &TieBreak::default(),
+            &Budget::default(),
+        );
+        assert!(ranked.windows(2).all(|w| w[0].1 >= w[1].1));
+
+        let mut active = vec![];
+        let step = perform_nth_step(
+            &mut Mode::MaxWeightedFacetCounting(count),
+            &mut counter,
+            &mut active,
+            &among,
+            2,
+            &TieBreak::default(),
+            &Budget::default(),
+        );
+        assert_eq!(step.map(|(f, _)| vec![f]), Some(active));
+
+        Ok(())
+    }
+
+    #[test]
+    fn tiny_ties() -> Result<()> {
+        let mut nav = Navigator::new(TINY, vec!["0".to_string()])?;
+        let active: [String; 0] = [];
+        let among = nav
+            .facet_inducing_atoms(active.iter())
+            .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
+            .ok_or(NavigatorError::None)?;
+        let count = Some(2 * among.len());
+        let mut counter = Counter::new(&mut nav, None);
+        let mode = Mode::MaxWeightedFacetCounting(count);
+
+        for (ties, expected) in [
+            (TieBreak::Lexicographic, "a"),
+            (
+                TieBreak::Priority(vec!["d".to_string(), "c".to_string()]),
+                "d",
+            ),
+        ] {
+            for _ in 0..9 {
+                let step = mode.propose_facet_within(
+                    &mut counter,
+                    &active,
+                    &among,
+                    &ties,
+                    &Budget::default(),
+                );
+                assert_eq!(step, Some((expected.to_string(), Some(0))));
+            }
+        }
+
+        let ties = TieBreak::Random(7);
+        let step =
+            mode.propose_facet_within(&mut counter, &active, &among, &ties, &Budget::default());
+        for _ in 0..9 {
+            assert_eq!(
+                mode.propose_facet_within(&mut counter, &active, &among, &ties, &Budget::default()),
+                step
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn tiny_as_first_step() -> Result<()> {
+        let mut nav = Navigator::new(TINY, vec!["0".to_string()])?;
+        let active: [String; 0] = [];
+        let among = nav
+            .facet_inducing_atoms(active.iter())
+            .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
+            .ok_or(NavigatorError::None)?;
+
+        // no or stale previous count
+        for count in [None, Some(100)] {
+            let mut counter = Counter::new(&mut nav, None);
+            let ranked = Mode::MaxWeightedAnswerSetCounting(count).rank_facets_within(
+                &mut counter,
+                &active,
+                &among,
+                &TieBreak::default(),
+                &Budget::default(),
+            );
+            assert_eq!(ranked.len(), 2 * among.len());
+            assert!(ranked.contains(&("~b".to_string(), Some(1))));
+            assert!(ranked.contains(&("b".to_string(), Some(2))));
+
+            let ranked = Mode::MinWeightedAnswerSetCounting(count).rank_facets_within(
+                &mut counter,
+                &active,
+                &among,
+                &TieBreak::default(),
+                &Budget::default(),
+            );
+            assert!(ranked.iter().all(|(_, c)| c.is_some_and(|c| c < 3)));
+            assert_eq!(ranked[0].1, Some(2));
+
+            if let Some((f, c)) = Mode::MaxWeightedAnswerSetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
+                &among,
+                &TieBreak::default(),
+                &Budget::default(),
+            ) {
+                assert_eq!(c, Some(1));
+                assert!(["a", "c", "d", "~b"].contains(&f.as_str()));
+            } else {
+                panic!()
+            }
+            if let Some((f, c)) = Mode::MinWeightedAnswerSetCounting(count).propose_facet_within(
+                &mut Counter::new(&mut nav, None),
+                &active,
+                &among,
+                &TieBreak::default(),
+                &Budget::default(),
+            ) {
+                assert_eq!(c, Some(2));
+                assert!(["~a", "~c", "~d", "b"].contains(&f.as_str()));
+            } else {
+                panic!()
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn q8_random_walk() -> Result<()> {
+        let mut nav = Navigator::new(Q8, vec!["0".to_string()])?;
+        let facets = |nav: &mut Navigator, active: &Vec<String>| {
+            nav.facet_inducing_atoms(active.iter())
+                .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
+                .ok_or(NavigatorError::None)
+        };
+
+        for seed in 0..3 {
+            let mut walks = vec![];
+            for _ in 0..2 {
+                let mut mode = Mode::Random(Some(seed));
+                let mut active = vec![];
+                let mut among = facets(&mut nav, &active)?;
+                while perform_next_step(
+                    &mut mode,
+                    &mut Counter::new(&mut nav, None),
+                    &mut active,
+                    &among,
+                    &TieBreak::default(),
+                    &Budget::default(),
+                )
+                .is_some()
+                {
+                    among = facets(&mut nav, &active)?;
+                }
+                assert_eq!(
+                    nav.enumerate_solutions_quietly(None, active.iter()).ok(),
+                    Some(1)
+                );
+                walks.push(active);
+            }
+            assert_eq!(walks[0], walks[1]);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn tiny_weighted() -> Result<()> {
+        let mut nav = Navigator::new(TINY, vec!["0".to_string()])?;
+        let mut active = vec![];
+        let among = nav
+            .facet_inducing_atoms(active.iter())
+            .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
+            .ok_or(NavigatorError::None)?;
+        let mut counter = Counter::new(&mut nav, None);
+
+        // facet counting by default
+        let ranked = rank_weighted(
+            &Mode::MaxWeighted(None),
+            &mut counter,
+            &active,
+            &among,
+            &TieBreak::Lexicographic,
+            &Budget::default(),
+        );
+        assert_eq!(ranked.len(), 2 * among.len());
+        assert_eq!(ranked[0], ("a".to_string(), 0.0));
+
+        let ranked = rank_weighted(
+            &Mode::MinWeighted(None),
+            &mut counter,
+            &active,
+            &among,
+            &TieBreak::Lexicographic,
+            &Budget::default(),
+        );
+        assert_eq!(ranked[0], ("~c".to_string(), 6.0));
+
+        let step = perform_next_step(
+            &mut Mode::MaxWeighted(None),
+            &mut counter,
+            &mut active,
+            &among,
+            &TieBreak::Lexicographic,
+            &Budget::default(),
+        );
+        assert_eq!(step, Some(("a".to_string(), None)));
+        assert_eq!(active, ["a"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn tiny_projected_as() -> Result<()> {
+        let mut nav = Navigator::new(
+            format!("{TINY} #show a/0. #show b/0."),
+            vec!["0".to_string()],
+        )?;
+        let active: [String; 0] = [];
+        let among = ["a", "b", "c", "d"].map(|f| f.to_string());
+        let mut counter = Counter::new(&mut nav, None).projecting();
+
+        let ranked = Mode::MaxWeightedAnswerSetCounting(None).rank_facets_within(
+            &mut counter,
+            &active,
+            &among,
+            &TieBreak::Lexicographic,
+            &Budget::default(),
+        );
+        // projected solutions {a} and {b}, the first one under ~c as well as under c
+        assert!(ranked.contains(&("c".to_string(), Some(1))));
+        assert!(ranked.contains(&("~c".to_string(), Some(2))));
+        assert!(ranked.contains(&("~a".to_string(), Some(1))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn tiny_interleaved() -> Result<()> {
+        let mut nav = Navigator::new(TINY, vec!["0".to_string()])?;
+        let facets = |nav: &mut Navigator, active: &Vec<String>| {
+            nav.facet_inducing_atoms(active.iter())
+                .map(|xs| xs.iter().map(|x| repr(*x)).collect::<Vec<_>>())
+                .ok_or(NavigatorError::None)
+        };
+        let mut active = vec![];
+        let mut mode = Mode::MaxWeightedFacetCounting(None);
+
+        let among = facets(&mut nav, &active)?;
+        let mut counter = Counter::new(&mut nav, None);
+        let step = perform_next_step(
+            &mut mode,
+            &mut counter,
+            &mut active,
+            &among,
+            &TieBreak::default(),
+            &Budget::default(),
+        );
+        assert_eq!(step.as_ref().map(|(_, c)| *c), Some(Some(0)));
+        mode.update(Some(0));
+
+        // manual deactivation, while the mode still holds the count after its step
+        active.pop();
+        let step = perform_next_step(
+            &mut mode,
+            &mut counter,
+            &mut active,
+            &among,
+            &TieBreak::default(),
+            &Budget::default(),
+        );
+        assert_eq!(step.map(|(_, c)| c), Some(Some(0)));
+        active.pop();
+        assert_eq!(mode.reference_count(&mut counter, &active, &among), Some(8));
+
+        // manual activation
+        active.push("b".to_string());
+        let among = facets(counter.nav, &active)?;
+        assert_eq!(mode.reference_count(&mut counter, &active, &among), Some(4));
+        let step = perform_next_step(
+            &mut mode,
+            &mut counter,
+            &mut active,
+            &among,
+            &TieBreak::default(),
+            &Budget::default(),
+        );
+        assert_eq!(step.map(|(_, c)| c), Some(Some(0)));
+        assert_eq!(active.len(), 2);
+
+        Ok(())
+    }
 }
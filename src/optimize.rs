@@ -0,0 +1,134 @@
+use clingo::{control, Part, SolveMode};
+use std::fmt;
+
+/// Constraints that restrict answer sets to those satisfying each literal in **route**.
+fn route_constraints(route: &[String]) -> String {
+    route
+        .iter()
+        .map(|l| match l.strip_prefix('~') {
+            Some(a) => format!(":- {a}."),
+            _ => format!(":- not {l}."),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns the optimum costs, by descending priority, of answer sets of **program** under
+/// **route**, solved with arguments **args**, or `None` if there is no answer set.
+///
+/// Costs are empty if **program** has no optimisation statements.
+pub fn optimum(program: &str, args: &[String], route: &[String]) -> Option<Vec<i64>> {
+    let constraints = route_constraints(route);
+
+    let mut ctl = control(args.to_vec()).ok()?;
+    ctl.add("base", &[], &format!("{program}\n{constraints}"))
+        .ok()?;
+    ctl.ground(&[Part::new("base", vec![]).ok()?]).ok()?;
+
+    // NOTE: models are reported with decreasing costs
+    let mut costs = None;
+    let mut handle = ctl.solve(SolveMode::YIELD, &[]).ok()?;
+    while let Ok(Some(model)) = handle.model() {
+        costs = model.cost().ok();
+        handle.resume().ok()?;
+    }
+    handle.close().ok()?;
+
+    costs
+}
+
+/// Answer sets that facets, counts and enumeration are restricted to.
+#[derive(Debug, Clone, Default)]
+pub enum Optimality {
+    #[default]
+    All,
+    /// Answer sets of optimum costs.
+    Optimal(Vec<i64>),
+    /// Answer sets of costs within a bound.
+    Bounded(Vec<i64>),
+}
+impl Optimality {
+    /// Restricts to optimal answer sets of **program**, solved with arguments **args**.
+    ///
+    /// The optimum is taken over all answer sets, hence the restriction remains valid as the
+    /// route changes.
+    pub fn optimal(program: &str, args: &[String]) -> Option<Self> {
+        optimum(program, args, &[]).map(Self::Optimal)
+    }
+
+    /// Solver arguments that restrict answer sets.
+    pub fn args(&self) -> Vec<String> {
+        match self {
+            Self::Optimal(costs) | Self::Bounded(costs) if !costs.is_empty() => {
+                let bound = costs
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",");
+                vec![format!("--opt-mode=enum,{bound}")]
+            }
+            _ => vec![],
+        }
+    }
+}
+impl fmt::Display for Optimality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => write!(f, "all answer sets"),
+            Self::Optimal(costs) => write!(f, "optimal answer sets {costs:?}"),
+            Self::Bounded(costs) => write!(f, "answer sets within bound {costs:?}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{strings, TINY};
+    use savan::nav::{
+        errors::*,
+        weights::{count, Weight},
+        Navigator,
+    };
+
+    const COSTS: &str = "a;b. c;d :- b. e. #minimize{ 3:a; 1:c; 2:d }.";
+
+    #[test]
+    fn optimum_under_routes() {
+        let args = ["0".to_string()];
+        assert_eq!(optimum(TINY, &args, &[]), Some(vec![]));
+        assert_eq!(optimum(COSTS, &args, &[]), Some(vec![1]));
+        assert_eq!(optimum(COSTS, &args, &["a".to_string()]), Some(vec![3]));
+        assert_eq!(optimum(COSTS, &args, &["~c".to_string()]), Some(vec![2]));
+        assert_eq!(
+            optimum(COSTS, &args, &["a".to_string(), "b".to_string()]),
+            None
+        );
+    }
+
+    #[test]
+    fn args() {
+        assert!(Optimality::All.args().is_empty());
+        assert!(Optimality::Optimal(vec![]).args().is_empty());
+        assert_eq!(
+            Optimality::Bounded(vec![4, 2]).args(),
+            ["--opt-mode=enum,4,2"]
+        );
+    }
+
+    #[test]
+    fn optimal_under_changed_route() -> Result<()> {
+        let args = strings(&["0"]);
+        let optimality = Optimality::optimal(COSTS, &args).ok_or(NavigatorError::None)?;
+        assert_eq!(optimality.args(), ["--opt-mode=enum,1"]);
+
+        let xs = args.iter().cloned().chain(optimality.args()).collect();
+        let mut nav = Navigator::new(COSTS, xs)?;
+        for (route, n) in [(vec![], 1), (strings(&["a"]), 0), (strings(&["b"]), 1)] {
+            let c = count(&mut Weight::AnswerSetCounting, &mut nav, route.iter());
+            assert_eq!(c, Some(n), "{route:?}");
+        }
+
+        Ok(())
+    }
+}
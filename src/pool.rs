@@ -0,0 +1,385 @@
+use crate::cache::{Cache, Key};
+use crate::weights::WeightFunction;
+use crate::wfc::{weighted_facet_count, WeightedFacet};
+use savan::nav::{
+    errors::Result,
+    weights::{count, count_projecting, Weight},
+    Navigator,
+};
+use std::sync::mpsc::{channel, Sender};
+use std::thread;
+
+/// Work on the solver of a worker, which is `None` if it could not be started.
+type Run = Box<dyn FnOnce(Option<&mut Navigator>) + Send>;
+
+struct Job {
+    program: String,
+    args: Vec<String>,
+    run: Run,
+}
+
+/// Worker threads, each owning a solver instance of the current program.
+pub struct Pool {
+    workers: Vec<Sender<Job>>,
+}
+impl Pool {
+    /// Spawns **n** workers, whose solvers use arguments **args** and start on **program**.
+    ///
+    /// Fails with the error of the first worker that could not start its solver.
+    pub fn new(n: usize, program: &str, args: Vec<String>) -> Result<Self> {
+        let (ready_tx, ready_rx) = channel::<Result<()>>();
+        let workers = (0..n)
+            .map(|_| {
+                let (tx, rx) = channel::<Job>();
+                let (program, args, ready) = (program.to_owned(), args.clone(), ready_tx.clone());
+                thread::spawn(move || {
+                    let mut solver = match Navigator::new(program.clone(), args.clone()) {
+                        Ok(nav) => {
+                            let _ = ready.send(Ok(()));
+                            (program, vec![], Some(nav))
+                        }
+                        Err(err) => {
+                            let _ = ready.send(Err(err));
+                            return;
+                        }
+                    };
+                    for job in rx {
+                        // NOTE: program changes with context and projection, arguments with
+                        // restrictions of answer sets
+                        if solver.0 != job.program || solver.1 != job.args {
+                            let xs = args.iter().chain(&job.args).cloned().collect();
+                            let nav = Navigator::new(job.program.clone(), xs)
+                                .map_err(|err| eprintln!("error: solver of pool failed: {err:?}"))
+                                .ok();
+                            solver = (job.program, job.args, nav);
+                        }
+                        (job.run)(solver.2.as_mut());
+                    }
+                });
+                tx
+            })
+            .collect();
+
+        drop(ready_tx);
+        for ready in ready_rx.iter().take(n) {
+            ready?;
+        }
+
+        Ok(Self { workers })
+    }
+
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Counts **routes** of **program** concurrently, returning counts in order of **routes**.
+    ///
+    /// Solvers use **args** in addition to the arguments of the pool.
+    pub fn count_all(
+        &self,
+        program: String,
+        args: &[String],
+        weight: &Weight,
+        projecting: bool,
+        routes: &[Vec<String>],
+    ) -> Vec<Option<usize>> {
+        let weight = weight.clone();
+        self.map(program, args, routes, move |nav, route| {
+            let mut weight = weight.clone();
+            match projecting {
+                true => count_projecting(&mut weight, nav, route.iter()),
+                _ => count(&mut weight, nav, route.iter()),
+            }
+        })
+    }
+
+    /// Weighs **routes** of **program** concurrently by weighted facet counting of **facets**,
+    /// returning weights in order of **routes**.
+    pub fn weigh_facets_all(
+        &self,
+        program: String,
+        args: &[String],
+        facets: &[WeightedFacet],
+        routes: &[Vec<String>],
+    ) -> Vec<Option<f64>> {
+        let facets = facets.to_vec();
+        self.map(program, args, routes, move |nav, route| {
+            weighted_facet_count(nav, route.to_vec(), facets.clone()).map(|w| w as f64)
+        })
+    }
+
+    /// Applies **f** to each of **routes** on the solvers of the workers, returning results in
+    /// order of **routes**.
+    fn map<T, F>(
+        &self,
+        program: String,
+        args: &[String],
+        routes: &[Vec<String>],
+        f: F,
+    ) -> Vec<Option<T>>
+    where
+        T: Send + 'static,
+        F: Fn(&mut Navigator, &[String]) -> Option<T> + Clone + Send + 'static,
+    {
+        let n = self.size();
+        let mut batches = vec![vec![]; n];
+        routes
+            .iter()
+            .enumerate()
+            .for_each(|(i, route)| batches[i % n].push((i, route.clone())));
+
+        let (tx, rx) = channel();
+        let mut pending = 0;
+        for (worker, routes) in self.workers.iter().zip(batches) {
+            if routes.is_empty() {
+                continue;
+            }
+            let (f, reply) = (f.clone(), tx.clone());
+            let run = move |mut nav: Option<&mut Navigator>| {
+                let xs = routes
+                    .into_iter()
+                    .map(|(i, route)| (i, nav.as_deref_mut().and_then(|nav| f(nav, &route))))
+                    .collect::<Vec<_>>();
+                let _ = reply.send(xs);
+            };
+            let job = Job {
+                program: program.clone(),
+                args: args.to_vec(),
+                run: Box::new(run),
+            };
+            if worker.send(job).is_ok() {
+                pending += 1;
+            }
+        }
+
+        let mut xs = (0..routes.len()).map(|_| None).collect::<Vec<_>>();
+        for (i, x) in rx.iter().take(pending).flatten() {
+            xs[i] = x;
+        }
+
+        xs
+    }
+}
+
+/// Counts routes on the navigator, or concurrently on a pool of solvers if available.
+///
+/// Counts are looked up in and stored to the cache if available.
+pub struct Counter<'a> {
+    pub nav: &'a mut Navigator,
+    pool: Option<&'a Pool>,
+    cache: Option<&'a mut Cache>,
+    weight: Option<&'a dyn WeightFunction>,
+    args: Vec<String>,
+    counted: usize,
+    pub projecting: bool,
+}
+impl<'a> Counter<'a> {
+    pub fn new(nav: &'a mut Navigator, pool: Option<&'a Pool>) -> Self {
+        Self {
+            nav,
+            pool,
+            cache: None,
+            weight: None,
+            args: vec![],
+            counted: 0,
+            projecting: false,
+        }
+    }
+
+    /// Shares counts with **cache**.
+    pub fn cached(mut self, cache: &'a mut Cache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Weighs routes by **weight**.
+    pub fn weighted(mut self, weight: &'a dyn WeightFunction) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    /// Distinguishes counts by solver arguments **args** of the navigator, which pool solvers
+    /// use as well.
+    pub fn restricted(mut self, args: Vec<String>) -> Self {
+        self.args = args;
+        self
+    }
+
+    /// Counts while projecting onto shown atoms.
+    pub fn projecting(mut self) -> Self {
+        self.projecting = true;
+        self
+    }
+
+    /// Number of routes counted on solvers so far, i.e., not found in the cache.
+    pub fn counted(&self) -> usize {
+        self.counted
+    }
+
+    /// Number of routes that are counted at once.
+    pub fn width(&self) -> usize {
+        self.pool.map_or(1, |pool| pool.size())
+    }
+
+    fn key(&self, weight: &Weight, program: &str, route: &[String]) -> Key {
+        let kind = match self.projecting {
+            true => format!("{weight:?} projecting"),
+            _ => format!("{weight:?}"),
+        };
+        Key::new(kind, program, &self.args, route)
+    }
+
+    pub fn count(&mut self, weight: &Weight, route: &[String]) -> Option<usize> {
+        self.count_all(weight, &[route.to_vec()]).pop().flatten()
+    }
+
+    /// Counts each of **routes**, returning counts in order of **routes**.
+    pub fn count_all(&mut self, weight: &Weight, routes: &[Vec<String>]) -> Vec<Option<usize>> {
+        let program = self.nav.program();
+        let keys = match self.cache {
+            Some(_) => routes
+                .iter()
+                .map(|route| Some(self.key(weight, &program, route)))
+                .collect::<Vec<_>>(),
+            _ => vec![None; routes.len()],
+        };
+        let mut counts = keys
+            .iter()
+            .map(|key| match (key, self.cache.as_mut()) {
+                (Some(key), Some(cache)) => cache.get(key),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let misses = (0..routes.len())
+            .filter(|i| counts[*i].is_none())
+            .collect::<Vec<_>>();
+        let missing = misses
+            .iter()
+            .map(|i| routes[*i].clone())
+            .collect::<Vec<_>>();
+        self.counted += missing.len();
+        let counted = match self.pool {
+            Some(pool) if missing.len() > 1 => {
+                pool.count_all(program, &self.args, weight, self.projecting, &missing)
+            }
+            _ => {
+                let mut weight = weight.clone();
+                missing
+                    .iter()
+                    .map(|route| match self.projecting {
+                        true => count_projecting(&mut weight, self.nav, route.iter()),
+                        _ => count(&mut weight, self.nav, route.iter()),
+                    })
+                    .collect()
+            }
+        };
+
+        for (i, c) in misses.into_iter().zip(counted) {
+            if let (Some(key), Some(cache), Some(c)) = (&keys[i], self.cache.as_mut(), c) {
+                cache.insert(key.clone(), c);
+            }
+            counts[i] = c;
+        }
+
+        counts
+    }
+
+    /// Weighs each of **routes** by weighted facet counting of **facets**, returning weights in
+    /// order of **routes**.
+    pub fn weigh_facets_all(
+        &mut self,
+        facets: &[WeightedFacet],
+        routes: &[Vec<String>],
+    ) -> Vec<Option<f64>> {
+        let program = self.nav.program();
+        let kind = format!("{:?}", Weight::FacetCounting);
+        let keys = routes
+            .iter()
+            .map(|route| {
+                let mut key = route.clone();
+                key.extend(
+                    facets
+                        .iter()
+                        .map(|f| format!("{} {}", f.literal(), f.weight())),
+                );
+                Key::new(format!("{kind} weighted"), &program, &self.args, &key)
+            })
+            .collect::<Vec<_>>();
+        let mut weights = keys
+            .iter()
+            .map(|key| self.cache.as_mut().and_then(|cache| cache.get_weight(key)))
+            .collect::<Vec<_>>();
+
+        let misses = (0..routes.len())
+            .filter(|i| weights[*i].is_none())
+            .collect::<Vec<_>>();
+        let missing = misses
+            .iter()
+            .map(|i| routes[*i].clone())
+            .collect::<Vec<_>>();
+        self.counted += missing.len();
+        let weighed = match self.pool {
+            Some(pool) if missing.len() > 1 => {
+                pool.weigh_facets_all(program, &self.args, facets, &missing)
+            }
+            _ => missing
+                .iter()
+                .map(|route| {
+                    weighted_facet_count(self.nav, route.clone(), facets.to_vec()).map(|w| w as f64)
+                })
+                .collect(),
+        };
+
+        for (i, w) in misses.into_iter().zip(weighed) {
+            if let (Some(cache), Some(w)) = (self.cache.as_mut(), w) {
+                cache.insert_weight(keys[i].clone(), w);
+            }
+            weights[i] = w;
+        }
+
+        weights
+    }
+
+    /// Weighs each of **routes** by the weight of this counter, facet counting by default.
+    pub fn weigh_all(&mut self, routes: &[Vec<String>]) -> Vec<Option<f64>> {
+        match self.weight {
+            Some(weight) => weight.weigh_all(self, routes),
+            _ => self
+                .count_all(&Weight::FacetCounting, routes)
+                .into_iter()
+                .map(|c| c.map(|c| c as f64))
+                .collect(),
+        }
+    }
+
+    /// Whether a zero weight of this counter implies that there are no facets.
+    pub fn is_counting(&self) -> bool {
+        self.weight.is_none_or(|w| w.is_counting())
+    }
+
+    pub fn weigh(&mut self, route: &[String]) -> Option<f64> {
+        self.weigh_all(&[route.to_vec()]).pop().flatten()
+    }
+
+    /// Counts under **route** extended by each of **facets** and its negation, in this order.
+    pub fn count_each(
+        &mut self,
+        weight: &Weight,
+        route: &[String],
+        facets: &[String],
+    ) -> Vec<(String, Option<usize>)> {
+        let lits = facets
+            .iter()
+            .flat_map(|f| [f.clone(), format!("~{f}")])
+            .collect::<Vec<_>>();
+        let routes = lits
+            .iter()
+            .map(|l| route.iter().chain([l]).cloned().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        lits.into_iter()
+            .zip(self.count_all(weight, &routes))
+            .collect()
+    }
+}
@@ -0,0 +1,148 @@
+use crate::modes::Budget;
+use crate::session::Session;
+use savan::nav::{weights::Weight, Navigator};
+
+/// Pseudo-random number generator (splitmix64), reproducible for a seed.
+pub struct SplitMix(u64);
+impl SplitMix {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number below **n**, which must be positive.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+/// Draws an answer set under **route**, whose facets are **facets**, returning the route
+/// that leads to it and the answer set.
+///
+/// Per step, a facet is included with probability equal to the fraction of answer sets that
+/// include it. Answer sets are drawn uniformly only if **facets** are the facets under
+/// **route**, e.g., no facets yield an arbitrary answer set instead of a uniformly drawn one.
+/// Once **budget** is exhausted, facets are included with probability one half, hence answer
+/// sets are no longer drawn uniformly.
+pub fn sample(
+    session: &mut Session,
+    nav: &mut Navigator,
+    route: &[String],
+    facets: &[String],
+    rng: &mut SplitMix,
+    budget: &Budget,
+) -> Option<(Vec<String>, Vec<String>)> {
+    let mut walk = route.to_vec();
+    let mut fs = facets.to_vec();
+    let mut total = session
+        .counter(nav)
+        .count(&Weight::AnswerSetCounting, &walk)?;
+
+    // NOTE: smallest facet to be independent of the order of facets
+    while let Some(f) = fs.iter().min().cloned() {
+        let lit = match budget.exhausted() || total == 0 {
+            true => match rng.below(2) {
+                0 => f,
+                _ => format!("~{f}"),
+            },
+            _ => {
+                walk.push(f.clone());
+                let included = session
+                    .counter(nav)
+                    .count(&Weight::AnswerSetCounting, &walk)?;
+                walk.pop();
+                match rng.below(total) < included {
+                    true => {
+                        total = included;
+                        f
+                    }
+                    _ => {
+                        total -= included;
+                        format!("~{f}")
+                    }
+                }
+            }
+        };
+        walk.push(lit);
+        fs = session.history.facets(nav, &walk)?;
+    }
+
+    let answer_set = nav.one_or_none(walk.iter())?;
+    Some((walk, answer_set))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::TINY;
+    use savan::nav::errors::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn tiny_uniform() -> Result<()> {
+        let mut nav = Navigator::new(TINY, vec!["0".to_string()])?;
+        let mut session = Session::default();
+        let facets = session
+            .history
+            .facets(&mut nav, &[])
+            .ok_or(NavigatorError::None)?;
+
+        let mut rng = SplitMix::new(42);
+        let mut seen = HashMap::new();
+        for _ in 0..300 {
+            let (_, mut answer_set) = sample(
+                &mut session,
+                &mut nav,
+                &[],
+                &facets,
+                &mut rng,
+                &Budget::default(),
+            )
+            .ok_or(NavigatorError::None)?;
+            answer_set.sort();
+            *seen.entry(answer_set).or_insert(0) += 1;
+        }
+
+        // answer sets {a,e}, {b,c,e} and {b,d,e} are drawn about 100 times each
+        assert_eq!(seen.len(), 3);
+        assert!(seen.values().all(|n| (60..=140).contains(n)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn tiny_reproducible() -> Result<()> {
+        let mut nav = Navigator::new(TINY, vec!["0".to_string()])?;
+        let mut session = Session::default();
+        let route = vec!["b".to_string()];
+        let facets = session
+            .history
+            .facets(&mut nav, &route)
+            .ok_or(NavigatorError::None)?;
+
+        let mut draw = |seed| {
+            sample(
+                &mut session,
+                &mut nav,
+                &route,
+                &facets,
+                &mut SplitMix::new(seed),
+                &Budget::default(),
+            )
+        };
+        for seed in 0..9 {
+            let xs = draw(seed).ok_or(NavigatorError::None)?;
+            assert_eq!(Some(&xs), draw(seed).as_ref());
+            assert!(xs.0.starts_with(&route));
+        }
+
+        Ok(())
+    }
+}
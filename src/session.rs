@@ -0,0 +1,80 @@
+use crate::cache::Cache;
+use crate::history::History;
+use crate::optimize::Optimality;
+use crate::pool::{Counter, Pool};
+use crate::ties::TieBreak;
+use crate::weights::Registry;
+use savan::nav::Navigator;
+use std::time::Duration;
+
+/// Settings that persist across commands.
+#[derive(Default)]
+pub struct Session {
+    /// Solver arguments given at startup.
+    pub args: Vec<String>,
+    /// Answer sets that navigation is restricted to.
+    pub optimality: Optimality,
+    /// Time budget of a single step proposal, unbounded if `None`.
+    pub timeout: Option<Duration>,
+    /// Solvers that score facets concurrently, sequential scoring if `None`.
+    pub pool: Option<Pool>,
+    /// Counts computed so far.
+    pub cache: Cache,
+    /// Facets computed under earlier routes.
+    pub history: History,
+    /// Order of step candidates with equal count.
+    pub ties: TieBreak,
+    /// Regex over atoms that steps project answer sets onto, no projection if `None`.
+    pub projection: Option<String>,
+    /// Weight functions, the active one weighs steps, tables and significance.
+    pub weights: Registry,
+    /// Answer sets of the last enumeration.
+    pub solutions: Vec<Vec<String>>,
+    /// Answer sets of the last search for representatives.
+    pub representatives: Vec<Vec<String>>,
+}
+impl Session {
+    /// Solver arguments given at startup, extended by restrictions of answer sets.
+    pub fn solver_args(&self) -> Vec<String> {
+        [self.args.clone(), self.optimality.args()].concat()
+    }
+
+    /// Answer sets that **id** addresses, i.e., solutions for `i` and representatives for `ri`,
+    /// and the index of the answer set within them, counting `i` from 1.
+    pub fn addressed(&self, id: &str) -> Option<(&[Vec<String>], usize)> {
+        let (answer_sets, i) = match id.strip_prefix('r') {
+            Some(i) => (&self.representatives, i),
+            _ => (&self.solutions, id),
+        };
+        i.parse::<usize>()
+            .ok()
+            .and_then(|i| i.checked_sub(1))
+            .filter(|i| *i < answer_sets.len())
+            .map(|i| (answer_sets.as_slice(), i))
+    }
+
+    /// Answer set addressed by **id**, see [Session::addressed].
+    pub fn answer_set(&self, id: &str) -> Option<&Vec<String>> {
+        self.addressed(id).map(|(answer_sets, i)| &answer_sets[i])
+    }
+
+    /// Counts on **nav**, using the pool and active weight of this session, but no cache.
+    pub fn uncached_counter<'a>(&'a self, nav: &'a mut Navigator) -> Counter<'a> {
+        let counter = Counter::new(nav, self.pool.as_ref()).restricted(self.optimality.args());
+        match self.weights.active() {
+            Some(weight) => counter.weighted(weight),
+            _ => counter,
+        }
+    }
+
+    /// Counts on **nav**, using the pool, cache and active weight of this session.
+    pub fn counter<'a>(&'a mut self, nav: &'a mut Navigator) -> Counter<'a> {
+        let counter = Counter::new(nav, self.pool.as_ref())
+            .cached(&mut self.cache)
+            .restricted(self.optimality.args());
+        match self.weights.active() {
+            Some(weight) => counter.weighted(weight),
+            _ => counter,
+        }
+    }
+}
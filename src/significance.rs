@@ -1,118 +1,368 @@
+use crate::pool::Counter;
 use regex::Regex;
-use savan::nav::{
-    weights::{count, count_projecting, Weight},
-    Navigator,
-};
+use savan::nav::errors::{NavigatorError, Result};
+use savan::nav::weights::Weight;
+
+/// Significance of **facet** for some literal, i.e., the fraction of the weight under the literal
+/// that is eliminated by including (**inclusive**) or excluding (**exclusive**) **facet**.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignificanceRow {
+    pub facet: String,
+    pub inclusive: f64,
+    pub exclusive: f64,
+}
+
+/// Column that significance rows are sorted by, in descending order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortBy {
+    Inclusive,
+    Exclusive,
+}
+
+/// Selection of significance rows that are reported.
+#[derive(Debug, Clone, Default)]
+pub struct Selection {
+    pub sort: Option<SortBy>,
+    /// Number of rows kept after sorting, all if `None`.
+    pub top: Option<usize>,
+    /// Least inclusive significance of kept rows.
+    pub inclusive: Option<f64>,
+    /// Least exclusive significance of kept rows.
+    pub exclusive: Option<f64>,
+}
+impl Selection {
+    /// Parses options=[sort inc|exc, top k, inc t, exc t] from **args**.
+    pub fn parse<'a>(mut args: impl Iterator<Item = &'a str>) -> Option<Self> {
+        let mut selection = Self::default();
+        while let Some(option) = args.next() {
+            match (option, args.next()) {
+                ("sort", Some("inc")) => selection.sort = Some(SortBy::Inclusive),
+                ("sort", Some("exc")) => selection.sort = Some(SortBy::Exclusive),
+                ("top", Some(k)) => selection.top = Some(k.parse::<usize>().ok()?),
+                ("inc", Some(t)) => selection.inclusive = Some(t.parse::<f64>().ok()?),
+                ("exc", Some(t)) => selection.exclusive = Some(t.parse::<f64>().ok()?),
+                _ => return None,
+            }
+        }
+        Some(selection)
+    }
+
+    pub fn apply(&self, rows: Vec<SignificanceRow>) -> Vec<SignificanceRow> {
+        let mut rows = rows
+            .into_iter()
+            .filter(|r| self.inclusive.is_none_or(|t| r.inclusive >= t))
+            .filter(|r| self.exclusive.is_none_or(|t| r.exclusive >= t))
+            .collect::<Vec<_>>();
+        match self.sort {
+            Some(SortBy::Inclusive) => rows.sort_by(|x, y| {
+                y.inclusive
+                    .total_cmp(&x.inclusive)
+                    .then_with(|| x.facet.cmp(&y.facet))
+            }),
+            Some(SortBy::Exclusive) => rows.sort_by(|x, y| {
+                y.exclusive
+                    .total_cmp(&x.exclusive)
+                    .then_with(|| x.facet.cmp(&y.facet))
+            }),
+            _ => (),
+        }
+        if let Some(k) = self.top {
+            rows.truncate(k)
+        }
+        rows
+    }
+}
 
 pub trait Significance {
+    /// Significance of facets among **facet_inducing_atoms** that match **re** for literal **y**
+    /// under **route**, empty if a counting weight under **y** is zero.
     fn significance(
         &mut self,
         route: &[String],
-        y: String,
+        y: &str,
+        facet_inducing_atoms: &[String],
+        re: &Regex,
+    ) -> Result<Vec<SignificanceRow>>;
+    /// Like [Significance::significance], but as fraction of answer sets under **y**.
+    fn answer_set_significance(
+        &mut self,
+        route: &[String],
+        y: &str,
         facet_inducing_atoms: &[String],
-        re: Regex,
-    );
+        re: &Regex,
+    ) -> Result<Vec<SignificanceRow>>;
     fn significance_projecting(
         &mut self,
         route: &[String],
-        y: String,
+        y: &str,
         facet_inducing_atoms: &[String],
-        re: Regex,
-    );
+        re: &Regex,
+    ) -> Result<Vec<SignificanceRow>>;
+    /// Significance of facets among **facet_inducing_atoms** that match **re** for each of
+    /// **literals** under **route**, with all counts scored at once.
+    fn significance_matrix(
+        &mut self,
+        route: &[String],
+        literals: &[String],
+        facet_inducing_atoms: &[String],
+        re: &Regex,
+    ) -> Result<Vec<(String, Vec<SignificanceRow>)>>;
+}
+
+/// Fraction of **weight** that is eliminated by reducing it to **reduced**, zero if there is no
+/// weight.
+fn eliminated(weight: f64, reduced: f64) -> f64 {
+    match weight == 0.0 {
+        true => 0.0,
+        _ => 1.0 - (reduced / weight),
+    }
+}
+
+/// Significance rows of facets among **facet_inducing_atoms** that match **re** for literal **y**
+/// under **route**, where **weigh** weighs routes and is **counting** if a zero weight implies
+/// that there are no facets.
+fn rows(
+    counter: &mut Counter,
+    mut weigh: impl FnMut(&mut Counter, &[Vec<String>]) -> Vec<Option<f64>>,
+    counting: bool,
+    route: &[String],
+    y: &str,
+    facet_inducing_atoms: &[String],
+    re: &Regex,
+) -> Result<Vec<SignificanceRow>> {
+    let mut ctx = route.to_vec();
+    ctx.push(y.to_owned());
+
+    let fc = weigh(counter, &[ctx.clone()])
+        .pop()
+        .flatten()
+        .ok_or(NavigatorError::None)?;
+    if counting && fc == 0.0 {
+        return Ok(vec![]);
+    }
+
+    let fs = facet_inducing_atoms
+        .iter()
+        .filter(|f| re.is_match(f))
+        .cloned()
+        .collect::<Vec<_>>();
+    let routes = fs
+        .iter()
+        .flat_map(|a| [a.clone(), format!("~{a}")])
+        .map(|l| ctx.iter().chain([&l]).cloned().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let weights = weigh(counter, &routes);
+
+    fs.into_iter()
+        .zip(weights.chunks(2))
+        .map(|(facet, ws)| {
+            let fc_a = ws[0].ok_or(NavigatorError::None)?;
+            let fc_a_exc = ws[1].ok_or(NavigatorError::None)?;
+            Ok(SignificanceRow {
+                facet,
+                inclusive: eliminated(fc, fc_a),
+                exclusive: eliminated(fc, fc_a_exc),
+            })
+        })
+        .collect()
 }
 
-impl Significance for Navigator {
+impl Significance for Counter<'_> {
     fn significance(
         &mut self,
         route: &[String],
-        y: String,
+        y: &str,
         facet_inducing_atoms: &[String],
-        re: Regex,
-    ) {
-        let mut ctx = route.to_vec();
-        ctx.push(y.clone());
-
-        let fc = count(&mut Weight::FacetCounting, self, ctx.iter()).unwrap() as f32;
-
-        if fc == 0.0 {
-            if self
-                .enumerate_solutions_quietly(Some(1), ctx.iter())
-                .is_ok_and(|n| n < 1)
-            {
-                println!("no facets, no answer set");
-            } else {
-                println!("no facets, unique answer set");
-            }
-        } else {
-            println!(" inc   exc");
-            for a in facet_inducing_atoms.iter().filter(|f| re.is_match(f)) {
-                let fc_a = unsafe {
-                    count(
-                        &mut Weight::FacetCounting,
-                        self,
-                        ctx.iter().chain([a.clone()].iter()),
-                    )
-                    .unwrap_unchecked() as f32
-                };
-
-                let fc_a_exc = unsafe {
-                    count(
-                        &mut Weight::FacetCounting,
-                        self,
-                        ctx.iter().chain([format!("~{a}")].iter()),
-                    )
-                    .unwrap_unchecked() as f32
-                };
+        re: &Regex,
+    ) -> Result<Vec<SignificanceRow>> {
+        let weigh = |counter: &mut Counter, routes: &[Vec<String>]| counter.weigh_all(routes);
+        let counting = self.is_counting();
+        rows(self, weigh, counting, route, y, facet_inducing_atoms, re)
+    }
 
-                println!("{:.3} {:.3} {a}", 1.0 - (fc_a / fc), 1.0 - (fc_a_exc / fc));
-            }
-        }
+    fn answer_set_significance(
+        &mut self,
+        route: &[String],
+        y: &str,
+        facet_inducing_atoms: &[String],
+        re: &Regex,
+    ) -> Result<Vec<SignificanceRow>> {
+        let weigh = |counter: &mut Counter, routes: &[Vec<String>]| {
+            counter
+                .count_all(&Weight::AnswerSetCounting, routes)
+                .into_iter()
+                .map(|c| c.map(|c| c as f64))
+                .collect()
+        };
+        rows(self, weigh, true, route, y, facet_inducing_atoms, re)
     }
 
     fn significance_projecting(
         &mut self,
         route: &[String],
-        y: String,
+        y: &str,
         facet_inducing_atoms: &[String],
-        re: Regex,
-    ) {
-        let mut ctx = route.to_vec();
-        ctx.push(y.clone());
-
-        let fc = count_projecting(&mut Weight::FacetCounting, self, ctx.iter()).unwrap() as f32;
-
-        if fc == 0.0 {
-            if self
-                .enumerate_solutions_quietly(Some(1), ctx.iter())
-                .is_ok_and(|n| n < 1)
-            {
-                println!("no facets, no answer set");
-            } else {
-                println!("no facets, unique answer set");
-            }
-        } else {
-            println!(" inc   exc");
-            for a in facet_inducing_atoms.iter().filter(|f| re.is_match(f)) {
-                let fc_a = unsafe {
-                    count_projecting(
-                        &mut Weight::FacetCounting,
-                        self,
-                        ctx.iter().chain([a.clone()].iter()),
-                    )
-                    .unwrap_unchecked() as f32
-                };
+        re: &Regex,
+    ) -> Result<Vec<SignificanceRow>> {
+        let projecting = std::mem::replace(&mut self.projecting, true);
+        let rows = self.significance(route, y, facet_inducing_atoms, re);
+        self.projecting = projecting;
+        rows
+    }
 
-                let fc_a_exc = unsafe {
-                    count_projecting(
-                        &mut Weight::FacetCounting,
-                        self,
-                        ctx.iter().chain([format!("~{a}")].iter()),
-                    )
-                    .unwrap_unchecked() as f32
+    fn significance_matrix(
+        &mut self,
+        route: &[String],
+        literals: &[String],
+        facet_inducing_atoms: &[String],
+        re: &Regex,
+    ) -> Result<Vec<(String, Vec<SignificanceRow>)>> {
+        let ctxs = literals
+            .iter()
+            .map(|y| route.iter().chain([y]).cloned().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        // NOTE: base weights are computed once per literal
+        let counting = self.is_counting();
+        let fcs = self
+            .weigh_all(&ctxs)
+            .into_iter()
+            .map(|fc| fc.ok_or(NavigatorError::None))
+            .collect::<Result<Vec<_>>>()?;
+
+        let fs = facet_inducing_atoms
+            .iter()
+            .filter(|f| re.is_match(f))
+            .cloned()
+            .collect::<Vec<_>>();
+        let routes = ctxs
+            .iter()
+            .zip(&fcs)
+            .filter(|(_, fc)| !counting || **fc != 0.0)
+            .flat_map(|(ctx, _)| {
+                fs.iter()
+                    .flat_map(|a| [a.clone(), format!("~{a}")])
+                    .map(|l| ctx.iter().chain([&l]).cloned().collect::<Vec<_>>())
+            })
+            .collect::<Vec<_>>();
+        let mut weights = self.weigh_all(&routes).into_iter();
+
+        literals
+            .iter()
+            .zip(fcs)
+            .map(|(y, fc)| {
+                let rows = match counting && fc == 0.0 {
+                    true => vec![],
+                    _ => fs
+                        .iter()
+                        .map(|facet| {
+                            let fc_a = weights.next().flatten().ok_or(NavigatorError::None)?;
+                            let fc_a_exc = weights.next().flatten().ok_or(NavigatorError::None)?;
+                            Ok(SignificanceRow {
+                                facet: facet.clone(),
+                                inclusive: eliminated(fc, fc_a),
+                                exclusive: eliminated(fc, fc_a_exc),
+                            })
+                        })
+                        .collect::<Result<Vec<_>>>()?,
                 };
+                Ok((y.clone(), rows))
+            })
+            .collect()
+    }
+}
 
-                println!("{:.3} {:.3} {a}", 1.0 - (fc_a / fc), 1.0 - (fc_a_exc / fc));
-            }
+/// Quotes **field** for CSV if necessary.
+pub fn csv_field(field: &str) -> String {
+    match field.contains([',', '"', '\n']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        _ => field.to_owned(),
+    }
+}
+
+/// Significance **matrix** as CSV, with a row per literal and columns for including and excluding
+/// each of **facets**, empty where a counting weight under the literal is zero.
+pub fn matrix_to_csv(facets: &[String], matrix: &[(String, Vec<SignificanceRow>)]) -> String {
+    let header = facets
+        .iter()
+        .flat_map(|f| [csv_field(f), csv_field(&format!("~{f}"))]);
+    let mut csv = ["literal".to_owned()]
+        .into_iter()
+        .chain(header)
+        .collect::<Vec<_>>()
+        .join(",");
+    csv.push('\n');
+    for (y, rows) in matrix {
+        let cells = match rows.is_empty() {
+            true => vec![String::new(); 2 * facets.len()],
+            _ => rows
+                .iter()
+                .flat_map(|r| [format!("{:.3}", r.inclusive), format!("{:.3}", r.exclusive)])
+                .collect(),
+        };
+        csv.push_str(
+            &[csv_field(y)]
+                .into_iter()
+                .chain(cells)
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        csv.push('\n');
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(facet: &str, inclusive: f64, exclusive: f64) -> SignificanceRow {
+        SignificanceRow {
+            facet: facet.to_owned(),
+            inclusive,
+            exclusive,
         }
     }
+
+    #[test]
+    fn selects() {
+        let rows = [row("a", 0.5, 0.25), row("b", 0.75, 0.0), row("c", 0.5, 0.5)];
+
+        let selection = Selection::parse("sort inc top 2".split_whitespace()).unwrap();
+        assert_eq!(
+            selection.apply(rows.to_vec()),
+            [row("b", 0.75, 0.0), row("a", 0.5, 0.25)]
+        );
+
+        let selection = Selection::parse("exc 0.25 sort exc".split_whitespace()).unwrap();
+        assert_eq!(
+            selection.apply(rows.to_vec()),
+            [row("c", 0.5, 0.5), row("a", 0.5, 0.25)]
+        );
+
+        assert!(Selection::parse("top".split_whitespace()).is_none());
+        assert!(Selection::parse("sort both".split_whitespace()).is_none());
+    }
+
+    #[test]
+    fn eliminates() {
+        assert_eq!(eliminated(8.0, 2.0), 0.75);
+        assert_eq!(eliminated(2.0, 3.0), -0.5);
+        assert_eq!(eliminated(0.0, 3.0), 0.0);
+    }
+
+    #[test]
+    fn matrix_csv() {
+        let facets = ["a".to_owned(), "p(1,2)".to_owned()];
+        let matrix = [
+            (
+                "b".to_owned(),
+                vec![row("a", 0.5, 0.25), row("p(1,2)", 1.0, 0.0)],
+            ),
+            ("~b".to_owned(), vec![]),
+        ];
+        assert_eq!(
+            matrix_to_csv(&facets, &matrix),
+            "literal,a,~a,\"p(1,2)\",\"~p(1,2)\"\nb,0.500,0.250,1.000,0.000\n~b,,,,\n"
+        );
+    }
 }
@@ -0,0 +1,349 @@
+use crate::significance::csv_field;
+use clingo::{control, Part, ShowType, SolveMode};
+use savan::nav::errors::Result;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+/// Number of answer sets of an enumeration that are kept, the ones beyond are only reported.
+pub const CAPACITY: usize = 1 << 12;
+
+/// Enumerates up to **n** answer sets of **program** under **route**, all if `None` or zero,
+/// solved with arguments **args**.
+///
+/// Each answer set is passed to **found** along with its index as soon as it is found. Returns
+/// the number of answer sets found and the first [`CAPACITY`] of them.
+pub fn enumerate(
+    program: &str,
+    args: &[String],
+    route: &[String],
+    n: Option<usize>,
+    mut found: impl FnMut(usize, &[String]),
+) -> Result<(usize, Vec<Vec<String>>)> {
+    let mut ctl = control(args.to_vec())?;
+    ctl.add("base", &[], program)?;
+    ctl.ground(&[Part::new("base", vec![])?])?;
+
+    let mut literals = HashMap::new();
+    for atom in ctl.symbolic_atoms()?.iter()? {
+        literals.insert(atom.symbol()?.to_string(), atom.literal()?);
+    }
+    // NOTE: unknown atoms are dropped from assumptions, as on the navigator
+    let assumptions = route
+        .iter()
+        .filter_map(|l| match l.strip_prefix('~') {
+            Some(a) => literals.get(a).map(|l| l.negate()),
+            _ => literals.get(l).copied(),
+        })
+        .collect::<Vec<_>>();
+
+    let (mut i, mut answer_sets) = (0, vec![]);
+    let mut handle = ctl.solve(SolveMode::YIELD, &assumptions)?;
+    while let Ok(Some(model)) = handle.model() {
+        let atoms = model
+            .symbols(ShowType::SHOWN)?
+            .iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<_>>();
+        found(i, &atoms);
+        if answer_sets.len() < CAPACITY {
+            answer_sets.push(atoms);
+        }
+
+        i += 1;
+        if n.is_some_and(|n| i == n) {
+            break;
+        }
+        handle.resume()?;
+    }
+    handle.close()?;
+
+    Ok((i, answer_sets))
+}
+
+/// Atoms only in **x**, only in **y**, and in both, each sorted.
+pub fn diff(x: &[String], y: &[String]) -> (Vec<String>, Vec<String>, Vec<String>) {
+    let (xs, ys) = (
+        x.iter().collect::<HashSet<_>>(),
+        y.iter().collect::<HashSet<_>>(),
+    );
+    let sorted = |atoms: HashSet<&String>| {
+        let mut atoms = atoms.into_iter().cloned().collect::<Vec<_>>();
+        atoms.sort();
+        atoms
+    };
+
+    (
+        sorted(xs.difference(&ys).copied().collect()),
+        sorted(ys.difference(&xs).copied().collect()),
+        sorted(xs.intersection(&ys).copied().collect()),
+    )
+}
+
+/// Literals over **facets** that hold in **answer_set**.
+pub fn literals(answer_set: &[String], facets: &[String]) -> Vec<String> {
+    let xs = answer_set.iter().collect::<HashSet<_>>();
+    facets
+        .iter()
+        .map(|f| match xs.contains(f) {
+            true => f.clone(),
+            _ => format!("~{f}"),
+        })
+        .collect()
+}
+
+/// Literals over **facets** of the **i**-th of **answer_sets** that together exclude the other
+/// answer sets, chosen greedily along with the number of answer sets each excludes additionally.
+///
+/// Answer sets that agree with the **i**-th on all **facets** cannot be excluded.
+pub fn distinguishing(
+    answer_sets: &[Vec<String>],
+    i: usize,
+    facets: &[String],
+) -> Vec<(String, usize)> {
+    let lits = literals(&answer_sets[i], facets);
+    let mut others = answer_sets
+        .iter()
+        .enumerate()
+        .filter(|(j, _)| *j != i)
+        .map(|(_, x)| literals(x, facets).into_iter().collect::<HashSet<_>>())
+        .collect::<Vec<_>>();
+
+    let mut picked = vec![];
+    loop {
+        // NOTE: ties are broken by order of facets
+        let best = lits
+            .iter()
+            .map(|l| (l, others.iter().filter(|x| !x.contains(l)).count()))
+            .rev()
+            .max_by_key(|(_, n)| *n);
+        match best {
+            Some((l, n)) if n > 0 => {
+                others.retain(|x| x.contains(l));
+                picked.push((l.clone(), n));
+            }
+            _ => break,
+        }
+    }
+    picked
+}
+
+/// Quotes **s** as JSON string.
+fn json_string(s: &str) -> String {
+    let mut quoted = "\"".to_owned();
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// **answer_sets** in the JSON format of clingo's `--outf=2`, where **more** tells whether
+/// enumeration stopped before all answer sets were found, and **time** is the total time spent.
+pub fn to_json(answer_sets: &[Vec<String>], more: bool, time: Duration) -> String {
+    let witnesses = answer_sets
+        .iter()
+        .map(|answer_set| {
+            let value = answer_set
+                .iter()
+                .map(|a| json_string(a))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("        {{\n          \"Value\": [{value}]\n        }}")
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+    let result = match answer_sets.is_empty() {
+        true => "UNSATISFIABLE",
+        _ => "SATISFIABLE",
+    };
+    let more = match more {
+        true => "yes",
+        _ => "no",
+    };
+
+    [
+        "{".to_owned(),
+        format!(
+            "  \"Solver\": {},",
+            json_string(&format!(
+                "{} v{}",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_VERSION")
+            ))
+        ),
+        "  \"Input\": [],".to_owned(),
+        "  \"Call\": [".to_owned(),
+        "    {".to_owned(),
+        format!("      \"Witnesses\": [\n{witnesses}\n      ]"),
+        "    }".to_owned(),
+        "  ],".to_owned(),
+        format!("  \"Result\": \"{result}\","),
+        format!(
+            "  \"Models\": {{\n    \"Number\": {},\n    \"More\": \"{more}\"\n  }},",
+            answer_sets.len()
+        ),
+        "  \"Calls\": 1,".to_owned(),
+        format!(
+            "  \"Time\": {{\n    \"Total\": {:.3}\n  }}",
+            time.as_secs_f64()
+        ),
+        "}\n".to_owned(),
+    ]
+    .join("\n")
+}
+
+/// **answer_sets** as CSV, with a row per answer set and a column per atom among **atoms** that
+/// holds 1 if the answer set contains the atom, 0 otherwise.
+pub fn to_csv(answer_sets: &[Vec<String>], atoms: &[String]) -> String {
+    let header = ["solution".to_owned()]
+        .into_iter()
+        .chain(atoms.iter().map(|a| csv_field(a)))
+        .collect::<Vec<_>>()
+        .join(",");
+    let mut csv = format!("{header}\n");
+    for (i, answer_set) in answer_sets.iter().enumerate() {
+        let xs = answer_set.iter().collect::<HashSet<_>>();
+        let row = [(i + 1).to_string()]
+            .into_iter()
+            .chain(atoms.iter().map(|a| (xs.contains(a) as u8).to_string()))
+            .collect::<Vec<_>>()
+            .join(",");
+        csv.push_str(&format!("{row}\n"));
+    }
+    csv
+}
+
+/// Jaccard similarity of **xs** and **ys**, one if both are empty.
+fn similarity(xs: &HashSet<&String>, ys: &HashSet<&String>) -> f64 {
+    match xs.union(ys).count() {
+        0 => 1.0,
+        n => xs.intersection(ys).count() as f64 / n as f64,
+    }
+}
+
+/// Groups indices of **answer_sets** into clusters, such that answer sets of Jaccard similarity at
+/// least **threshold** share a cluster, and clusters are ordered by their least index.
+pub fn cluster(answer_sets: &[Vec<String>], threshold: f64) -> Vec<Vec<usize>> {
+    let sets = answer_sets
+        .iter()
+        .map(|x| x.iter().collect::<HashSet<_>>())
+        .collect::<Vec<_>>();
+
+    // NOTE: single linkage, each answer set is labelled by the least index of its cluster
+    let mut labels = (0..sets.len()).collect::<Vec<_>>();
+    for i in 0..sets.len() {
+        for j in i + 1..sets.len() {
+            let (li, lj) = (labels[i], labels[j]);
+            if li != lj && similarity(&sets[i], &sets[j]) >= threshold {
+                let (keep, drop) = (li.min(lj), li.max(lj));
+                labels
+                    .iter_mut()
+                    .filter(|l| **l == drop)
+                    .for_each(|l| *l = keep);
+            }
+        }
+    }
+
+    let mut clusters: Vec<Vec<usize>> = vec![];
+    for (i, label) in labels.into_iter().enumerate() {
+        match clusters.iter_mut().find(|c| c[0] == label) {
+            Some(c) => c.push(i),
+            _ => clusters.push(vec![i]),
+        }
+    }
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{strings, TINY};
+
+    #[test]
+    fn tiny_enumerate() -> Result<()> {
+        let args = strings(&["0"]);
+        let mut seen = vec![];
+        let (n, answer_sets) = enumerate(TINY, &args, &[], None, |i, xs| seen.push((i, xs.len())))?;
+        assert_eq!(n, 3);
+        assert_eq!(seen.len(), 3);
+        assert!(answer_sets.contains(&strings(&["a", "e"])));
+
+        assert_eq!(enumerate(TINY, &args, &[], Some(2), |_, _| ())?.0, 2);
+        let (n, answer_sets) = enumerate(TINY, &args, &strings(&["b", "~d"]), None, |_, _| ())?;
+        assert_eq!(n, 1);
+        assert_eq!(diff(&answer_sets[0], &strings(&["b", "c", "e"])).0.len(), 0);
+        // NOTE: unknown atoms are dropped from assumptions
+        assert_eq!(
+            enumerate(TINY, &args, &strings(&["a", "x"]), None, |_, _| ())?.0,
+            1
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn diff_and_cluster() {
+        let answer_sets = [
+            strings(&["a", "e"]),
+            strings(&["b", "c", "e"]),
+            strings(&["b", "d", "e"]),
+        ];
+
+        assert_eq!(
+            diff(&answer_sets[1], &answer_sets[2]),
+            (strings(&["c"]), strings(&["d"]), strings(&["b", "e"]))
+        );
+
+        assert_eq!(cluster(&answer_sets, 0.5), [vec![0], vec![1, 2]]);
+        assert_eq!(cluster(&answer_sets, 0.2), [vec![0, 1, 2]]);
+        assert_eq!(cluster(&answer_sets, 1.0), [vec![0], vec![1], vec![2]]);
+    }
+
+    #[test]
+    fn distinguishing_literals() {
+        let answer_sets = [
+            strings(&["a", "e"]),
+            strings(&["b", "c", "e"]),
+            strings(&["b", "d", "e"]),
+        ];
+        let facets = strings(&["a", "b", "c", "d"]);
+
+        assert_eq!(literals(&answer_sets[0], &facets), ["a", "~b", "~c", "~d"]);
+        assert_eq!(
+            distinguishing(&answer_sets, 0, &facets),
+            [("a".to_owned(), 2)]
+        );
+        assert_eq!(
+            distinguishing(&answer_sets, 1, &facets),
+            [("c".to_owned(), 2)]
+        );
+        assert!(distinguishing(&answer_sets[..1], 0, &facets).is_empty());
+    }
+
+    #[test]
+    fn exports() {
+        let answer_sets = [strings(&["a", "e"]), strings(&["p(\"x\",1)"])];
+
+        let json = to_json(&answer_sets, true, Duration::from_millis(1500));
+        assert!(json.contains(r#""Value": ["a", "e"]"#));
+        assert!(json.contains(r#""Value": ["p(\"x\",1)"]"#));
+        assert!(json.contains(r#""Result": "SATISFIABLE","#));
+        assert!(json.contains(r#""Number": 2,"#));
+        assert!(json.contains(r#""More": "yes""#));
+        assert!(json.contains(r#""Total": 1.500"#));
+        assert!(to_json(&[], false, Duration::ZERO).contains(r#""Result": "UNSATISFIABLE","#));
+
+        let atoms = strings(&["a", "p(\"x\",1)"]);
+        assert_eq!(
+            to_csv(&answer_sets, &atoms),
+            "solution,a,\"p(\"\"x\"\",1)\"\n1,1,0\n2,0,1\n"
+        );
+    }
+}
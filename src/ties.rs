@@ -0,0 +1,112 @@
+use crate::wfc::parse_weighted_facets_from_file;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Policy that orders candidate literals of equal count.
+#[derive(Debug, Clone, Default)]
+pub enum TieBreak {
+    /// Order in which the solver returns facets.
+    #[default]
+    Solver,
+    /// Lexicographic order of literals.
+    Lexicographic,
+    /// Order of predicates in a priority list, lexicographic otherwise.
+    Priority(Vec<String>),
+    /// Descending user weights of literals, lexicographic otherwise.
+    Weights(HashMap<String, f32>),
+    /// Pseudo-random order determined by a seed.
+    Random(u64),
+}
+impl TieBreak {
+    /// Reads weights of literals from **filename**, lines of the form `facet inclusive weight`.
+    pub fn from_weights(filename: &str) -> Option<Self> {
+        parse_weighted_facets_from_file(filename).map(|wfcs| {
+            Self::Weights(
+                wfcs.iter()
+                    .map(|wfc| (wfc.literal(), wfc.weight()))
+                    .collect(),
+            )
+        })
+    }
+
+    /// Compares literals **x** and **y**, lesser literals are preferred.
+    pub fn cmp(&self, x: &str, y: &str) -> Ordering {
+        match self {
+            Self::Solver => Ordering::Equal,
+            Self::Lexicographic => x.cmp(y),
+            Self::Priority(preds) => {
+                let rank = |l: &str| {
+                    preds
+                        .iter()
+                        .position(|p| p == predicate(l))
+                        .unwrap_or(preds.len())
+                };
+                rank(x).cmp(&rank(y)).then_with(|| x.cmp(y))
+            }
+            Self::Weights(ws) => {
+                let weight = |l: &str| ws.get(l).copied().unwrap_or(0.0);
+                weight(y)
+                    .partial_cmp(&weight(x))
+                    .unwrap_or(Ordering::Equal)
+                    .then_with(|| x.cmp(y))
+            }
+            Self::Random(seed) => mix(*seed, x).cmp(&mix(*seed, y)).then_with(|| x.cmp(y)),
+        }
+    }
+}
+impl fmt::Display for TieBreak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Solver => write!(f, "solver"),
+            Self::Lexicographic => write!(f, "lex"),
+            Self::Priority(preds) => write!(f, "priority {}", preds.join(" ")),
+            Self::Weights(ws) => write!(f, "weights ({} literals)", ws.len()),
+            Self::Random(seed) => write!(f, "random {seed}"),
+        }
+    }
+}
+
+fn predicate(literal: &str) -> &str {
+    let atom = literal.strip_prefix('~').unwrap_or(literal);
+    atom.split('(').next().unwrap_or(atom)
+}
+
+/// Hashes **literal** under **seed**, independent of platform and compiler version.
+pub fn mix(seed: u64, literal: &str) -> u64 {
+    // NOTE: FNV-1a followed by splitmix64 finalizer
+    let mut h = literal.bytes().fold(0xcbf29ce484222325 ^ seed, |h, b| {
+        (h ^ b as u64).wrapping_mul(0x100000001b3)
+    });
+    h = (h ^ (h >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94d049bb133111eb);
+    h ^ (h >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders() {
+        let mut lits = vec!["q(2)", "~p(1)", "p(3)", "r"];
+
+        lits.sort_by(|x, y| TieBreak::Lexicographic.cmp(x, y));
+        assert_eq!(lits, ["p(3)", "q(2)", "r", "~p(1)"]);
+
+        let priority = TieBreak::Priority(vec!["r".to_string(), "p".to_string()]);
+        lits.sort_by(|x, y| priority.cmp(x, y));
+        assert_eq!(lits, ["r", "p(3)", "~p(1)", "q(2)"]);
+
+        let weights = TieBreak::Weights(HashMap::from([("q(2)".to_string(), 2.0)]));
+        lits.sort_by(|x, y| weights.cmp(x, y));
+        assert_eq!(lits[0], "q(2)");
+
+        let random = TieBreak::Random(42);
+        let mut shuffled = lits.clone();
+        shuffled.sort_by(|x, y| random.cmp(x, y));
+        lits.reverse();
+        lits.sort_by(|x, y| random.cmp(x, y));
+        assert_eq!(lits, shuffled);
+    }
+}
@@ -0,0 +1,233 @@
+use crate::optimize::optimum;
+use crate::pool::Counter;
+use crate::wfc::{parse_weighted_facets_from_file, WeightedFacet};
+use savan::nav::weights::Weight;
+use std::collections::BTreeMap;
+
+/// Weight of routes, the lesser the weight the less uncertain the route.
+pub trait WeightFunction {
+    /// Weighs each of **routes** on **counter**, returning weights in order of **routes**.
+    fn weigh_all(&self, counter: &mut Counter, routes: &[Vec<String>]) -> Vec<Option<f64>>;
+    /// Whether a zero weight implies that there are no facets, as for counts.
+    fn is_counting(&self) -> bool {
+        false
+    }
+}
+
+/// Counts of savan, optionally projecting onto shown atoms.
+pub struct Counting {
+    pub weight: Weight,
+    pub projecting: bool,
+}
+impl WeightFunction for Counting {
+    fn weigh_all(&self, counter: &mut Counter, routes: &[Vec<String>]) -> Vec<Option<f64>> {
+        let projecting = counter.projecting;
+        counter.projecting |= self.projecting;
+        let counts = counter.count_all(&self.weight, routes);
+        counter.projecting = projecting;
+
+        counts.into_iter().map(|c| c.map(|c| c as f64)).collect()
+    }
+
+    fn is_counting(&self) -> bool {
+        true
+    }
+}
+
+/// Entropy in bits of the uniform distribution over answer sets.
+pub struct Entropy;
+impl WeightFunction for Entropy {
+    fn weigh_all(&self, counter: &mut Counter, routes: &[Vec<String>]) -> Vec<Option<f64>> {
+        counter
+            .count_all(&Weight::AnswerSetCounting, routes)
+            .into_iter()
+            .map(|c| c.map(|c| (c.max(1) as f64).log2()))
+            .collect()
+    }
+
+    fn is_counting(&self) -> bool {
+        true
+    }
+}
+
+/// Facet counting, where facets are weighted as given in a file.
+pub struct WeightedFacetCounting(pub Vec<WeightedFacet>);
+impl WeightFunction for WeightedFacetCounting {
+    fn weigh_all(&self, counter: &mut Counter, routes: &[Vec<String>]) -> Vec<Option<f64>> {
+        counter.weigh_facets_all(&self.0, routes)
+    }
+}
+
+/// Optimum cost at the highest priority, zero without optimisation statements, using solver
+/// arguments as given.
+pub struct Cost(pub Vec<String>);
+impl WeightFunction for Cost {
+    fn weigh_all(&self, counter: &mut Counter, routes: &[Vec<String>]) -> Vec<Option<f64>> {
+        let program = counter.nav.program();
+        routes
+            .iter()
+            .map(|route| {
+                optimum(&program, &self.0, route).map(|cs| cs.first().map_or(0.0, |c| *c as f64))
+            })
+            .collect()
+    }
+}
+
+/// Weight functions by name, one of which is active.
+pub struct Registry {
+    weights: BTreeMap<String, Box<dyn WeightFunction>>,
+    active: String,
+    /// Weight to activate again on [Registry::restore].
+    previous: Option<String>,
+}
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+impl Registry {
+    /// Builtin weights, where costs are computed with solver arguments **args**.
+    pub fn new(args: &[String]) -> Self {
+        let mut registry = Self {
+            weights: BTreeMap::new(),
+            active: "fc".to_owned(),
+            previous: None,
+        };
+        for (name, weight, projecting) in [
+            ("fc", Weight::FacetCounting, false),
+            ("as", Weight::AnswerSetCounting, false),
+            ("fc*", Weight::FacetCounting, true),
+            ("as*", Weight::AnswerSetCounting, true),
+        ] {
+            registry.register(name, Box::new(Counting { weight, projecting }));
+        }
+        registry.register("entropy", Box::new(Entropy));
+        registry.register("cost", Box::new(Cost(args.to_vec())));
+
+        registry
+    }
+
+    /// Registers **weight** under **name**, replacing any weight of the same name.
+    pub fn register(&mut self, name: &str, weight: Box<dyn WeightFunction>) {
+        self.weights.insert(name.to_owned(), weight);
+    }
+
+    /// Registers weighted facet counting under **name**, with weights from **filename**.
+    pub fn load(&mut self, name: &str, filename: &str) -> Option<()> {
+        let wfcs = parse_weighted_facets_from_file(filename)?;
+        self.register(name, Box::new(WeightedFacetCounting(wfcs)));
+        Some(())
+    }
+
+    /// Activates the weight registered under **name**, returning whether there is one.
+    pub fn select(&mut self, name: &str) -> bool {
+        let known = self.weights.contains_key(name);
+        if known {
+            self.active = name.to_owned();
+            self.previous = None;
+        }
+        known
+    }
+
+    /// Like [Registry::select], but the weight active before is activated again on
+    /// [Registry::restore].
+    pub fn select_temporarily(&mut self, name: &str) -> bool {
+        let previous = self.previous.take().unwrap_or(self.active.clone());
+        let known = self.select(name);
+        self.previous = Some(previous);
+        known
+    }
+
+    /// Activates the weight that was active before [Registry::select_temporarily], unless some
+    /// weight was selected since.
+    pub fn restore(&mut self) {
+        if let Some(name) = self.previous.take() {
+            self.active = name;
+        }
+    }
+
+    pub fn active(&self) -> Option<&dyn WeightFunction> {
+        self.weights.get(&self.active).map(|w| w.as_ref())
+    }
+
+    pub fn active_name(&self) -> &str {
+        &self.active
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.weights.keys()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::Cache;
+    use crate::fixtures::TINY;
+    use savan::nav::{errors::*, Navigator};
+
+    #[test]
+    fn tiny_builtins() -> Result<()> {
+        let mut nav = Navigator::new(TINY, vec!["0".to_string()])?;
+        let mut registry = Registry::default();
+        let routes = [vec![], vec!["b".to_string()], vec!["a".to_string()]];
+
+        let expected = [
+            ("fc", [8.0, 4.0, 0.0]),
+            ("as", [3.0, 2.0, 1.0]),
+            ("entropy", [3f64.log2(), 1.0, 0.0]),
+        ];
+        for (name, weights) in expected {
+            assert!(registry.select(name));
+            let weight = registry.active().ok_or(NavigatorError::None)?;
+            let mut counter = Counter::new(&mut nav, None).weighted(weight);
+            assert_eq!(
+                counter.weigh_all(&routes),
+                weights.map(Some).to_vec(),
+                "{name}"
+            );
+        }
+
+        assert!(!registry.select("unknown"));
+        assert_eq!(registry.active_name(), "entropy");
+
+        assert!(registry.select_temporarily("cost"));
+        assert!(registry.select_temporarily("cost"));
+        registry.restore();
+        assert_eq!(registry.active_name(), "entropy");
+        registry.select_temporarily("cost");
+        registry.select("as");
+        registry.restore();
+        assert_eq!(registry.active_name(), "as");
+
+        Ok(())
+    }
+
+    #[test]
+    fn tiny_weighted_facets_cached() -> Result<()> {
+        let mut nav = Navigator::new(TINY, vec!["0".to_string()])?;
+        let mut cache = Cache::default();
+        let mut registry = Registry::default();
+        let filename = std::env::temp_dir().join("fasb-tiny-weighted-facets.txt");
+        std::fs::write(&filename, "a 1 2.0\nc 0 1.5\n").map_err(|_| NavigatorError::None)?;
+        registry
+            .load("wfc", &filename.to_string_lossy())
+            .ok_or(NavigatorError::None)?;
+        assert!(registry.select("wfc"));
+        let weight = registry.active().ok_or(NavigatorError::None)?;
+        let routes = [vec![], vec!["b".to_string()], vec!["a".to_string()]];
+
+        for counted in [3, 0] {
+            let mut counter = Counter::new(&mut nav, None)
+                .cached(&mut cache)
+                .weighted(weight);
+            assert_eq!(
+                counter.weigh_all(&routes),
+                vec![Some(0.0), Some(0.0), Some(3.5)]
+            );
+            assert_eq!(counter.counted(), counted);
+        }
+
+        Ok(())
+    }
+}
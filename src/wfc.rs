@@ -6,6 +6,18 @@ pub struct WeightedFacet {
     inclusive: bool,
     weight: f32,
 }
+impl WeightedFacet {
+    pub fn literal(&self) -> String {
+        match self.inclusive {
+            true => self.facet.clone(),
+            _ => format!("~{}", self.facet),
+        }
+    }
+
+    pub fn weight(&self) -> f32 {
+        self.weight
+    }
+}
 
 pub fn weighted_facet_count(
     nav: &mut Navigator,